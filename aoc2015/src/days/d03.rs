@@ -1,21 +1,27 @@
 use std::collections::HashSet;
+use utils::geom::{Dir4, Point2};
+use utils::viz::{Animation, Cell};
 use utils::PuzzleResult;
 
 pub fn part1(input: &str) -> PuzzleResult {
-    let directions: Vec<Direction> = input
+    let directions: Vec<Dir4> = input
         .trim()
         .chars()
         .map(|c| c.try_into().unwrap())
         .collect();
 
-    let mut visited: HashSet<(i32, i32)> = HashSet::new();
-    let mut position = (0, 0);
+    let mut visited: HashSet<Point2<i32>> = HashSet::new();
+    let mut position = Point2::origin();
     visited.insert(position); // need to add the origin
 
+    let mut animation = Animation::new("2015 day 3: houses visited by Santa");
+    animation.plot(position, Cell::colored('#', [0, 192, 0]));
     for d in directions {
-        let delta = d.delta();
-        position = (position.0 + delta.0 as i32, position.1 + delta.1 as i32);
+        position += d.delta();
         visited.insert(position);
+
+        animation.points([(position, Cell::colored('@', [255, 0, 0]))], position);
+        animation.plot(position, Cell::colored('#', [0, 192, 0]));
     }
 
     Ok(format!("{}", visited.len()))
@@ -23,79 +29,36 @@ pub fn part1(input: &str) -> PuzzleResult {
 
 pub fn part2(input: &str) -> PuzzleResult {
     let input = input.trim();
-    let santa_directions: Vec<Direction> = input
+    let santa_directions: Vec<Dir4> = input
         .chars()
         .step_by(2)
         .map(|c| c.try_into().unwrap())
         .collect();
-    let robo_directions: Vec<Direction> = input
+    let robo_directions: Vec<Dir4> = input
         .chars()
         .skip(1)
         .step_by(2)
         .map(|c| c.try_into().unwrap())
         .collect();
 
-    let mut visited: HashSet<(i32, i32)> = HashSet::new();
-    let mut santa_position = (0, 0);
-    let mut robo_position = (0, 0);
+    let mut visited: HashSet<Point2<i32>> = HashSet::new();
+    let mut santa_position = Point2::origin();
+    let mut robo_position = Point2::origin();
     visited.insert(santa_position); // need to add the origin
 
     for d in santa_directions {
-        let delta = d.delta();
-        santa_position = (
-            santa_position.0 + delta.0 as i32,
-            santa_position.1 + delta.1 as i32,
-        );
+        santa_position += d.delta();
         visited.insert(santa_position);
     }
 
     for d in robo_directions {
-        let delta = d.delta();
-        robo_position = (
-            robo_position.0 + delta.0 as i32,
-            robo_position.1 + delta.1 as i32,
-        );
+        robo_position += d.delta();
         visited.insert(robo_position);
     }
 
     Ok(format!("{}", visited.len()))
 }
 
-#[derive(Debug)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
-}
-
-impl Direction {
-    const fn delta(&self) -> (i8, i8) {
-        use Direction::*;
-        match self {
-            North => (0, 1),
-            South => (0, -1),
-            East => (1, 0),
-            West => (-1, 0),
-        }
-    }
-}
-
-impl TryFrom<char> for Direction {
-    type Error = String;
-
-    fn try_from(c: char) -> Result<Self, Self::Error> {
-        use Direction::*;
-        match c {
-            '^' => Ok(North),
-            'v' => Ok(South),
-            '>' => Ok(East),
-            '<' => Ok(West),
-            _ => Err(format!("Unknown: '{}'", c)),
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,46 +1,25 @@
-#[allow(unused_imports)]
-use md5::{Digest, Md5};
+use utils::hashsearch;
 use utils::PuzzleResult;
 
-pub fn part1(_input: &str) -> PuzzleResult {
-    // let secret_key = input.trim();
-    // let mut hasher = Md5::new();
-    // let mut answer: u32 = 0;
-
-    // loop {
-    //     hasher.update(format!("{}{}", secret_key, answer));
-    //     let hash = hasher.finalize_reset();
-    //     // Check for at least 5 leading zeroes in the hexadecimal output
-    //     if hash[0] == 0 && hash[1] == 0 && hash[2] < 16 {
-    //         return Ok(format!("{}", answer));
-    //     }
-    //     answer += 1;
-    // }
-    Ok("-- skipped --".into())
+pub fn part1(input: &str) -> PuzzleResult {
+    // Check for at least 5 leading zeroes in the hexadecimal output
+    let answer = hashsearch::lowest_suffix(input.trim(), 1, |h| hashsearch::leading_zeroes(h) >= 5);
+    Ok(format!("{}", answer))
 }
 
-pub fn part2(_input: &str) -> PuzzleResult {
-    // let secret_key = input.trim();
-    // let mut hasher = Md5::new();
-    // let mut answer: u32 = 0;
-
-    // loop {
-    //     hasher.update(format!("{}{}", secret_key, answer));
-    //     let hash = hasher.finalize_reset();
-    //     // Check for at least 6 leading zeroes in the hexadecimal output
-    //     if hash[0] == 0 && hash[1] == 0 && hash[2] == 0 {
-    //         return Ok(format!("{}", answer));
-    //     }
-    //     answer += 1;
-    // }
-    Ok("-- skipped --".into())
+pub fn part2(input: &str) -> PuzzleResult {
+    // Check for at least 6 leading zeroes in the hexadecimal output
+    let answer = hashsearch::lowest_suffix(input.trim(), 1, |h| hashsearch::leading_zeroes(h) >= 6);
+    Ok(format!("{}", answer))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Slow unless optimized; run with `cargo test -p aoc2015 --release -- --ignored`
     #[test]
+    #[ignore = "slow unless optimized"]
     fn p1() {
         let cases = vec![("abcdef", 609043), ("pqrstuv", 1048970)];
         for (input, expected) in cases {
@@ -1,39 +1,49 @@
+use std::error::Error;
+
+use macros::FromLine;
+use utils::rects::{Rect, RectMap};
+use utils::render;
 use utils::PuzzleResult;
 
 pub fn part1(input: &str) -> PuzzleResult {
-    let instructions: Vec<Instruction> = input.lines().map(|l| l.try_into().unwrap()).collect();
-    let mut g: Grid<p1::Light> = Grid::new();
+    let g: RectMap<p1::Light> = follow_instructions(input)?;
+    Ok(format!("{}", g.sum_by(|&l| (l == p1::Light::On) as usize)))
+}
 
-    for i in instructions {
-        match i.action() {
-            Action::TurnOn => g.turn_on(i.start(), i.stop()),
-            Action::TurnOff => g.turn_off(i.start(), i.stop()),
-            Action::Toggle => g.toggle(i.start(), i.stop()),
-        }
+pub fn part2(input: &str) -> PuzzleResult {
+    let g: RectMap<p2::Light> = follow_instructions(input)?;
+
+    if render::enabled() {
+        // Draw the lights at their brightest in white
+        let max = g
+            .iter()
+            .map(|(_, l)| l.brightness())
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        render::save_png("2015-d06", &g.to_grid(), |l| {
+            [(l.brightness() * 255 / max) as u8; 3]
+        })?;
     }
 
-    Ok(format!(
-        "{}",
-        g.lights().filter(|&&l| l == p1::Light::On).count()
-    ))
+    Ok(format!("{}", g.sum_by(|l| l.brightness())))
 }
 
-pub fn part2(input: &str) -> PuzzleResult {
-    let instructions: Vec<Instruction> = input.lines().map(|l| l.try_into().unwrap()).collect();
-    let mut g: Grid<p2::Light> = Grid::new();
-
-    for i in instructions {
+/// Set up a grid of [Light]s according to Santa's instructions
+fn follow_instructions<T: Light + Clone + PartialEq>(
+    input: &str,
+) -> Result<RectMap<T>, Box<dyn Error>> {
+    let mut g = new_grid();
+    for line in input.lines() {
+        let i = Instruction::try_from(line)?;
         match i.action() {
-            Action::TurnOn => g.turn_on(i.start(), i.stop()),
-            Action::TurnOff => g.turn_off(i.start(), i.stop()),
-            Action::Toggle => g.toggle(i.start(), i.stop()),
+            Action::TurnOn => g.turn_on(i.rect()),
+            Action::TurnOff => g.turn_off(i.rect()),
+            Action::Toggle => g.toggle(i.rect()),
         }
     }
 
-    Ok(format!(
-        "{}",
-        g.lights().map(|l| l.brightness()).sum::<usize>()
-    ))
+    Ok(g)
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -43,74 +53,67 @@ enum Action {
     Toggle,
 }
 
+/// An instruction to change a rectangle of lights; e.g., `turn on 0,0 through 999,999`
 #[derive(Debug, PartialEq)]
-struct Instruction((Action, (usize, usize), (usize, usize)));
+struct Instruction {
+    action: Action,
+    corners: Corners,
+}
+
+/// The (inclusive) corners of a rectangle of lights; e.g., `0,0 through 999,999`
+#[derive(Debug, PartialEq, FromLine)]
+#[format("{start} through {stop}")]
+struct Corners {
+    start: Corner,
+    stop: Corner,
+}
+
+/// The coordinates of a light; e.g., `499,500`
+#[derive(Debug, PartialEq, Clone, Copy, FromLine)]
+#[format("{x},{y}")]
+struct Corner {
+    x: usize,
+    y: usize,
+}
 
 impl Instruction {
     fn action(&self) -> Action {
-        self.0 .0
-    }
-
-    fn start(&self) -> (usize, usize) {
-        self.0 .1
+        self.action
     }
 
-    fn stop(&self) -> (usize, usize) {
-        self.0 .2
+    /// The rectangle of lights this instruction applies to. Coordinates are given as `(x, y)`, so
+    /// they're flipped to get `(row, col)`.
+    fn rect(&self) -> Rect {
+        let Corners { start, stop } = &self.corners;
+        Rect::new((start.y, start.x), (stop.y, stop.x))
     }
 }
 
 impl TryFrom<&str> for Instruction {
     type Error = Box<dyn std::error::Error>;
 
+    /// The corners are parsed by [FromLine]; only the action (which may be more than one word, so
+    /// can't be a field of the format) is matched here.
     fn try_from(s: &str) -> Result<Self, Self::Error> {
-        let mut split = s.split(" ");
-
-        let action = {
-            let mut action_str = split.next().ok_or("Could not get action string")?;
-
-            if action_str == "turn" {
-                action_str = split
-                    .next()
-                    .ok_or("Could not get second part of action string")?;
-            }
-
-            match action_str {
-                "on" => Ok(Action::TurnOn),
-                "off" => Ok(Action::TurnOff),
-                "toggle" => Ok(Action::Toggle),
-                _ => Err(format!("Invalid action string: {}", action_str)),
-            }
-        }?;
-
-        let start = {
-            let start_str = split.next().ok_or("Could not get start coords")?;
-
-            let mut start_split = start_str.split(",");
-            let x = start_split.next().ok_or("No start x coord")?.parse()?;
-            let y = start_split.next().ok_or("No start y coord")?.parse()?;
-
-            (x, y)
-        };
-
-        // Drop the 'through' portion of the input
-        split.next().ok_or("Missing 'through'")?;
-
-        let stop = {
-            let stop_str = split.next().ok_or("Could not get start coords")?;
-
-            let mut stop_split = stop_str.split(",");
-            let x = stop_split.next().ok_or("No start x coord")?.parse()?;
-            let y = stop_split.next().ok_or("No start y coord")?.parse()?;
+        let actions = [
+            ("turn on ", Action::TurnOn),
+            ("turn off ", Action::TurnOff),
+            ("toggle ", Action::Toggle),
+        ];
 
-            (x, y)
-        };
+        let (action, corners) = actions
+            .into_iter()
+            .find_map(|(prefix, action)| Some((action, s.strip_prefix(prefix)?)))
+            .ok_or_else(|| format!("Unknown action in: {s:?}"))?;
 
-        Ok(Self((action, start, stop)))
+        Ok(Self {
+            action,
+            corners: Corners::try_from(corners)?,
+        })
     }
 }
 
-/// Represent a single light in the [Grid] for either [part1] or [part2]
+/// Represent a single light in the grid for either [part1] or [part2]
 trait Light {
     /// Create a new (turned off) light
     fn new() -> Self;
@@ -122,99 +125,29 @@ trait Light {
 /// The side length of a square grid of [Light]s.
 const GRID_SIZE: usize = 1000;
 
-struct Grid<T>(Box<Vec<Vec<T>>>)
-where
-    T: Light + Clone + Copy;
-
-impl<T> Grid<T>
-where
-    T: Light + Clone + Copy,
-{
-    fn new() -> Self {
-        Self(Box::new(vec![vec![T::new(); GRID_SIZE]; GRID_SIZE]))
-    }
-
-    fn turn_on(&mut self, start: (usize, usize), stop: (usize, usize)) {
-        let (x1, y1) = start;
-        let (x2, y2) = stop;
-
-        for x in x1..=x2 {
-            for y in y1..=y2 {
-                self[x][y].turn_on();
-            }
-        }
-    }
-
-    fn turn_off(&mut self, start: (usize, usize), stop: (usize, usize)) {
-        let (x1, y1) = start;
-        let (x2, y2) = stop;
-
-        for x in x1..=x2 {
-            for y in y1..=y2 {
-                self[x][y].turn_off();
-            }
-        }
-    }
-
-    fn toggle(&mut self, start: (usize, usize), stop: (usize, usize)) {
-        let (x1, y1) = start;
-        let (x2, y2) = stop;
-
-        for x in x1..=x2 {
-            for y in y1..=y2 {
-                self[x][y].toggle();
-            }
-        }
-    }
-
-    fn lights(&self) -> impl Iterator<Item = &T> {
-        self.0.iter().flat_map(|col| col.iter())
-    }
+/// Create a grid of (turned off) [Light]s
+fn new_grid<T: Light + Clone + PartialEq>() -> RectMap<T> {
+    RectMap::new(Rect::of_size(GRID_SIZE, GRID_SIZE), T::new())
 }
 
-impl<T> std::ops::Index<usize> for Grid<T>
-where
-    T: Light + Clone + Copy,
-{
-    type Output = [T];
-
-    fn index(&self, index: usize) -> &Self::Output {
-        self.0.index(index).as_slice()
-    }
+/// Operations on the rectangles of [Light]s in a grid described by [Instruction]s
+trait Switchboard {
+    fn turn_on(&mut self, rect: Rect);
+    fn turn_off(&mut self, rect: Rect);
+    fn toggle(&mut self, rect: Rect);
 }
 
-impl<T> std::ops::IndexMut<usize> for Grid<T>
-where
-    T: Light + Clone + Copy,
-{
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        self.0.index_mut(index).as_mut_slice()
+impl<T: Light + Clone + PartialEq> Switchboard for RectMap<T> {
+    fn turn_on(&mut self, rect: Rect) {
+        self.update(rect, |l| l.turn_on());
     }
-}
 
-// Only used by a couple tests
-#[cfg(test)]
-impl<T> std::ops::Index<std::ops::RangeTo<usize>> for Grid<T>
-where
-    T: Light + Clone + Copy,
-{
-    type Output = [Vec<T>];
-
-    fn index(&self, index: std::ops::RangeTo<usize>) -> &Self::Output {
-        self.0.index(index)
+    fn turn_off(&mut self, rect: Rect) {
+        self.update(rect, |l| l.turn_off());
     }
-}
 
-// Only used by a couple tests
-#[cfg(test)]
-impl<T> std::ops::Index<std::ops::RangeFrom<usize>> for Grid<T>
-where
-    T: Light + Clone + Copy,
-{
-    type Output = [Vec<T>];
-
-    fn index(&self, index: std::ops::RangeFrom<usize>) -> &Self::Output {
-        self.0.index(index)
+    fn toggle(&mut self, rect: Rect) {
+        self.update(rect, |l| l.toggle());
     }
 }
 
@@ -253,74 +186,66 @@ mod p1 {
     mod tests {
         use super::super::*;
 
+        fn count_on(g: &RectMap<super::Light>) -> usize {
+            g.sum_by(|&l| (l == super::Light::On) as usize)
+        }
+
         #[test]
         fn turn_on() {
             // Turn on all the lights
-            let mut g: Grid<super::Light> = Grid::new();
-            g.turn_on((0, 0), (999, 999));
-            assert_eq!(
-                g.lights().filter(|&&l| l == super::Light::On).count(),
-                1_000_000,
-                "All lights should be on"
-            );
+            let mut g: RectMap<super::Light> = new_grid();
+            g.turn_on(Rect::new((0, 0), (999, 999)));
+            assert_eq!(count_on(&g), 1_000_000, "All lights should be on");
         }
 
         #[test]
         fn turn_off() {
             // Start with a fully on grid for testing
-            let mut g: Grid<super::Light> = Grid::new();
-            g.turn_on((0, 0), (999, 999));
-            assert_eq!(
-                g.lights().filter(|&&l| l == super::Light::On).count(),
-                1_000_000,
-                "All lights should be on"
-            );
+            let mut g: RectMap<super::Light> = new_grid();
+            g.turn_on(Rect::new((0, 0), (999, 999)));
+            assert_eq!(count_on(&g), 1_000_000, "All lights should be on");
 
             // Turn off only the middle 4 lights
-            g.turn_off((499, 499), (500, 500));
+            g.turn_off(Rect::new((499, 499), (500, 500)));
             for (row, col) in vec![(499, 499), (499, 500), (500, 499), (500, 500)] {
                 assert_eq!(
-                    g[row][col],
-                    super::Light::Off,
+                    g.get((row, col)),
+                    Some(&super::Light::Off),
                     "Middle four lights should be off"
                 );
             }
+            assert_eq!(count_on(&g), 999_996);
         }
 
         #[test]
         fn toggle() {
             // Light every other column of lights in the grid for testing
-            let mut g: Grid<super::Light> = Grid::new();
-            for row in 0..1000 {
-                for col in (0..1000).into_iter().filter(|i| i % 2 == 0) {
-                    g[row][col] = super::Light::On;
-                }
+            let mut g: RectMap<super::Light> = new_grid();
+            for col in (0..1000).step_by(2) {
+                g.turn_on(Rect::new((0, col), (999, col)));
             }
-            assert_eq!(
-                g.lights().filter(|&&l| l == super::Light::On).count(),
-                500_000,
-                "Half the lights should be on"
-            );
+            assert_eq!(count_on(&g), 500_000, "Half the lights should be on");
 
-            g.toggle((0, 0), (999, 999));
+            g.toggle(Rect::new((0, 0), (999, 999)));
             assert_eq!(
-                g.lights().filter(|&&l| l == super::Light::On).count(),
+                count_on(&g),
                 500_000,
                 "Half the lights should be on after toggle"
             );
 
+            let g = g.to_grid();
             for row in 0..1000 {
                 for col in 0..1000 {
                     if col % 2 == 0 {
                         assert!(
-                            g[row][col] == super::Light::Off,
+                            g[(row, col)] == super::Light::Off,
                             "Light at ({}, {}) should be off",
                             row,
                             col
                         );
                     } else {
                         assert!(
-                            g[row][col] == super::Light::On,
+                            g[(row, col)] == super::Light::On,
                             "Light at ({}, {}) should be on",
                             row,
                             col
@@ -373,16 +298,16 @@ mod p2 {
 
         #[test]
         fn turn_on() {
-            let mut g: Grid<super::Light> = Grid::new();
-            g.turn_on((0, 0), (0, 0));
-            assert!(g.lights().map(|l| l.brightness()).sum::<usize>() == 1);
+            let mut g: RectMap<super::Light> = new_grid();
+            g.turn_on(Rect::new((0, 0), (0, 0)));
+            assert!(g.sum_by(|l| l.brightness()) == 1);
         }
 
         #[test]
         fn toggle() {
-            let mut g: Grid<super::Light> = Grid::new();
-            g.toggle((0, 0), (999, 999));
-            assert!(g.lights().map(|l| l.brightness()).sum::<usize>() == 2_000_000);
+            let mut g: RectMap<super::Light> = new_grid();
+            g.toggle(Rect::new((0, 0), (999, 999)));
+            assert!(g.sum_by(|l| l.brightness()) == 2_000_000);
         }
 
         #[test]
@@ -412,24 +337,63 @@ mod p2 {
 mod tests {
     use super::*;
 
+    fn instruction(
+        action: Action,
+        (x1, y1): (usize, usize),
+        (x2, y2): (usize, usize),
+    ) -> Instruction {
+        Instruction {
+            action,
+            corners: Corners {
+                start: Corner { x: x1, y: y1 },
+                stop: Corner { x: x2, y: y2 },
+            },
+        }
+    }
+
     #[test]
     fn instruction_try_from() {
         let cases = vec![
             (
                 "turn on 0,0 through 999,999",
-                Instruction((Action::TurnOn, (0, 0), (999, 999))),
+                instruction(Action::TurnOn, (0, 0), (999, 999)),
             ),
             (
                 "toggle 0,0 through 999,0",
-                Instruction((Action::Toggle, (0, 0), (999, 0))),
+                instruction(Action::Toggle, (0, 0), (999, 0)),
             ),
             (
                 "turn off 499,499 through 500,500",
-                Instruction((Action::TurnOff, (499, 499), (500, 500))),
+                instruction(Action::TurnOff, (499, 499), (500, 500)),
             ),
         ];
         for (input, expected) in cases {
             assert_eq!(Instruction::try_from(input).unwrap(), expected);
         }
+
+        let err = Instruction::try_from("turn up 0,0 through 1,1").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"Unknown action in: "turn up 0,0 through 1,1""#
+        );
+        let err = Instruction::try_from("toggle 0,0 through 1,x").unwrap_err();
+        assert!(err.to_string().contains("Invalid `y` (\"x\")"), "{err}");
+    }
+
+    #[test]
+    fn follow_example_instructions() {
+        let input = "turn on 0,0 through 999,999\ntoggle 0,0 through 999,0\nturn off 499,499 through 500,500";
+        assert_eq!(part1(input).unwrap(), "998996");
+        assert_eq!(
+            part2("turn on 0,0 through 0,0\ntoggle 0,0 through 999,999").unwrap(),
+            "2000001"
+        );
+    }
+
+    #[test]
+    fn instruction_rect() {
+        // (x, y) coordinates are flipped to (row, col)
+        let i = Instruction::try_from("toggle 0,0 through 999,0").unwrap();
+        assert_eq!(i.rect(), Rect::new((0, 0), (0, 999)));
     }
 }
@@ -1,181 +1,61 @@
-use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use utils::exprgraph::{ExprGraph, Operand, Operator};
 use utils::PuzzleResult;
 
 // see: https://doc.rust-lang.org/book/appendix-02-operators.html
 
 pub fn part1(input: &str) -> PuzzleResult {
-    let c = Circuit::try_from(input)?;
-    let c = c.eval("a")?;
-    match &c["a"] {
-        Signal::Value(v) => Ok(format!("{}", &v)),
-        Signal::Expr(e) => Err(format!("Expected value, got expr: '{:?}'", e).into()),
-    }
+    let mut c = parse_circuit(input)?;
+    Ok(format!("{}", c.eval("a")?))
 }
 
 pub fn part2(input: &str) -> PuzzleResult {
-    let mut c = Circuit::try_from(input)?;
-    let override_signal = c.clone().eval("a")?["a"];
-
-    c.0.insert("b", override_signal);
-    let c = c.eval("a")?;
-
-    match c["a"] {
-        Signal::Value(v) => Ok(format!("{}", &v)),
-        Signal::Expr(e) => Err(format!("Expected value, got expr: '{:?}'", e).into()),
-    }
-}
+    let mut c = parse_circuit(input)?;
+    let override_signal = c.eval("a")?;
 
-/// A value on a wire in a [Circuit]
-#[derive(Debug, PartialEq, Clone, Copy)]
-enum Signal<'a> {
-    Value(u16),
-    Expr(Expr<'a>),
+    c.set_input("b", override_signal);
+    Ok(format!("{}", c.eval("a")?))
 }
 
-/// A Circuit is a number of wires (identified by one or more letters)
-/// that each carry a [Signal]
-#[derive(Debug, Clone)]
-struct Circuit<'a>(HashMap<&'a str, Signal<'a>>);
-
-impl<'a> Circuit<'a> {
-    /// Evaluate this circuit until the [Signal] on the specified wire is known.
-    // Use `mut self` instead of `&mut self` b/c passing ownership was easier than
-    // managing the lifetimes.
-    fn eval(mut self, wire: &'a str) -> Result<Self, Box<dyn std::error::Error>> {
-        match &self[wire] {
-            // Case 1: The value of the signal on this wire is known.
-            Signal::Value(_) => Ok(self),
-            // Case 2: The value of the signal on this wire is not yet known.
-            Signal::Expr(e) => {
-                match e.expr_type {
-                    // Case 2a: The value of the signal on this wire comes from another wire.
-                    // We must evaluate that wire to determine the signal on this wire.
-                    ExprType::Immediate => {
-                        let expr_wire = e.l.unwrap();
-                        self = self.eval(expr_wire)?;
-                        let expr_signal = self[expr_wire];
-                        self.0.insert(wire, expr_signal);
-                    }
-                    // Case 2b: The value of the signal on this wire comes from a gate.
-                    // We must evaluate the input to that gate, then the gate itself, to
-                    // determine the signal on this wire.
-                    ExprType::Unary => {
-                        let operator = e.op.unwrap();
-                        if operator != Gate::NOT {
-                            return Err("'NOT' is the only supported unary operator!".into());
-                        }
-
-                        let operand = e.r.unwrap();
-                        let operand = if let Ok(val) = operand.parse() {
-                            // The operand is an immediate value we hadn't yet parsed
-                            Signal::Value(val)
-                        } else {
-                            // The operand is a wire we must evaluate
-                            self = self.eval(operand)?;
-                            self[operand]
-                        };
+/// A Circuit is a number of wires (identified by one or more letters) that each carry a
+/// signal from a value, another wire, or a [Gate]. Use `to_dot()` to see what it looks like.
+type Circuit = ExprGraph<Gate, u16>;
 
-                        if let Signal::Value(v) = operand {
-                            self.0.insert(wire, Signal::Value(!v));
-                        } else {
-                            return Err(format!(
-                                "Operand should be a value at this point, got: {:?}",
-                                operand
-                            )
-                            .into());
-                        }
-                    }
-                    // Case 2c: The value of the signal on this wire comes from a gate.
-                    // We must evaluate the inputs to that gate, then the gate itself, to
-                    // determine the signal on this wire.
-                    ExprType::Binary => {
-                        let l_operand = e.l.unwrap();
-                        let r_operand = e.r.unwrap();
-                        let operator = e.op.unwrap();
+fn parse_circuit(input: &str) -> Result<Circuit, Box<dyn Error>> {
+    let mut circuit = Circuit::new();
 
-                        let l_operand = if let Ok(val) = l_operand.parse() {
-                            Signal::Value(val)
-                        } else {
-                            self = self.eval(l_operand)?;
-                            self[l_operand]
-                        };
+    for line in input.lines() {
+        let (expr, wire) = line
+            .split_once("->")
+            .ok_or_else(|| format!("Expected an expression & wire in: {line}"))?;
 
-                        let r_operand = if let Ok(val) = r_operand.parse() {
-                            Signal::Value(val)
-                        } else {
-                            self = self.eval(r_operand)?;
-                            self[r_operand]
-                        };
-
-                        let (l, r) = match (l_operand, r_operand) {
-                            (Signal::Value(l_val), Signal::Value(r_val)) => (l_val, r_val),
-                            _ => return Err(format!("Both operands should be values at this point. Got l: {:?}, r: {:?}", l_operand, r_operand).into())
-                        };
-
-                        let expr_signal = Signal::Value(match operator {
-                            Gate::AND => l & r,
-                            Gate::OR => l | r,
-                            Gate::LSHIFT => l << r,
-                            Gate::RSHIFT => l >> r,
-                            Gate::NOT => return Err("'NOT' is not a binary operator!".into()),
-                        });
+        // Operands are either immediate values or other wires
+        let mut operand = |s: &str| match s.parse() {
+            Ok(val) => Operand::Const(val),
+            Err(_) => Operand::Node(circuit.intern(s)),
+        };
 
-                        self.0.insert(wire, expr_signal);
-                    }
-                }
+        let (gate, args) = match expr.split_whitespace().collect::<Vec<_>>()[..] {
+            [l] => (Gate::WIRE, vec![operand(l)]),
+            [op, r] => (Gate::try_from(op)?, vec![operand(r)]),
+            [l, op, r] => (Gate::try_from(op)?, vec![operand(l), operand(r)]),
+            _ => return Err(format!("Invalid expression: {expr}").into()),
+        };
 
-                // Once evaluation is done, return this circuit
-                Ok(self)
-            }
-        }
+        circuit.define(wire.trim(), gate, args);
     }
-}
-
-impl<'a> TryFrom<&'a str> for Circuit<'a> {
-    type Error = Box<dyn std::error::Error>;
-
-    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
-        let mut map: HashMap<&str, Signal> = HashMap::new();
-
-        for line in input.lines().map(|l| {
-            let mut parts = l.trim().split("->");
-            let expr = Expr::try_from(parts.next().unwrap().trim())?;
-            let wire = parts.next().unwrap().trim();
-            Ok::<(&str, Expr<'_>), Self::Error>((wire, expr))
-        }) {
-            let (wire, expr) = line?;
 
-            // Only try to evaluate immediate expressions at this point; other expression
-            // types will be evaluated after the entire circuit is parsed.
-            let signal = match expr.expr_type {
-                ExprType::Immediate => {
-                    if let Ok(val) = expr.l.unwrap().parse() {
-                        Signal::Value(val)
-                    } else {
-                        Signal::Expr(expr)
-                    }
-                }
-                _ => Signal::Expr(expr),
-            };
-
-            map.insert(wire, signal);
-        }
-
-        Ok(Self(map))
-    }
-}
-
-impl<'a> std::ops::Index<&str> for Circuit<'a> {
-    type Output = Signal<'a>;
-
-    fn index(&self, index: &str) -> &Self::Output {
-        self.0.index(index)
-    }
+    Ok(circuit)
 }
 
 /// A single bitwise logic gate in a [Circuit]
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[allow(clippy::upper_case_acronyms)] // named as in the puzzle input
 enum Gate {
+    /// Not really a gate: the signal comes straight from a value or another wire
+    WIRE,
     AND,
     OR,
     LSHIFT,
@@ -183,6 +63,20 @@ enum Gate {
     NOT,
 }
 
+impl Operator<u16> for Gate {
+    fn apply(&self, args: &[u16]) -> Result<u16, String> {
+        match (self, args) {
+            (Gate::WIRE, &[v]) => Ok(v),
+            (Gate::NOT, &[v]) => Ok(!v),
+            (Gate::AND, &[l, r]) => Ok(l & r),
+            (Gate::OR, &[l, r]) => Ok(l | r),
+            (Gate::LSHIFT, &[l, r]) => Ok(l << r),
+            (Gate::RSHIFT, &[l, r]) => Ok(l >> r),
+            _ => Err(format!("Wrong number of inputs for {self}: {args:?}")),
+        }
+    }
+}
+
 impl TryFrom<&str> for Gate {
     type Error = String;
 
@@ -198,68 +92,16 @@ impl TryFrom<&str> for Gate {
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-enum ExprType {
-    Immediate,
-    Unary,
-    Binary,
-}
-
-/// An expression to evaluate while parsing a [Circuit]
-///
-/// * For [Immediate](ExprType::Immediate) expressions, there is only a `l` component
-/// * For [Unary](ExprType::Unary) expressions, there is an `op` and `r` component
-/// * For [Binary](ExprType::Binary) expressions, there are `l`, `op`, and `r` components
-#[derive(Debug, PartialEq, Clone, Copy)]
-struct Expr<'a> {
-    expr_type: ExprType,
-    l: Option<&'a str>,
-    op: Option<Gate>,
-    r: Option<&'a str>,
-}
-
-impl<'a> TryFrom<&'a str> for Expr<'a> {
-    type Error = String;
-
-    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
-        let expr_type = {
-            let count = value.split(" ").count();
-            if count == 1 {
-                ExprType::Immediate
-            } else if count == 2 {
-                ExprType::Unary
-            } else {
-                ExprType::Binary
-            }
-        };
-
-        let mut parts = value.split(" ");
-        match expr_type {
-            ExprType::Immediate => Ok(Self {
-                expr_type,
-                l: parts.next(),
-                op: None,
-                r: None,
-            }),
-            ExprType::Unary => Ok(Self {
-                expr_type,
-                l: None,
-                op: Some(Gate::try_from(parts.next().unwrap())?),
-                r: parts.next(),
-            }),
-            ExprType::Binary => Ok(Self {
-                expr_type,
-                l: parts.next(),
-                op: Some(Gate::try_from(parts.next().unwrap())?),
-                r: parts.next(),
-            }),
-        }
+impl fmt::Display for Gate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use utils::exprgraph::{EvalError, Expr};
 
     const INPUT: &'static str = r#"123 -> x
 456 -> y
@@ -272,37 +114,56 @@ NOT y -> i"#;
 
     #[test]
     fn parse_circuit() {
-        let expected = HashMap::from([
-            ("x", Signal::Value(123)),
-            ("y", Signal::Value(456)),
-            ("d", Signal::Expr(Expr::try_from("x AND y").unwrap())),
-            ("e", Signal::Expr(Expr::try_from("x OR y").unwrap())),
-            ("f", Signal::Expr(Expr::try_from("x LSHIFT 2").unwrap())),
-            ("g", Signal::Expr(Expr::try_from("y RSHIFT 2").unwrap())),
-            ("h", Signal::Expr(Expr::try_from("NOT x").unwrap())),
-            ("i", Signal::Expr(Expr::try_from("NOT y").unwrap())),
-        ]);
+        use Operand::*;
+        let circuit = super::parse_circuit(INPUT).unwrap();
+        let (x, y) = (circuit.id("x").unwrap(), circuit.id("y").unwrap());
+        let expected = vec![
+            ("x", Gate::WIRE, vec![Const(123)]),
+            ("y", Gate::WIRE, vec![Const(456)]),
+            ("d", Gate::AND, vec![Node(x), Node(y)]),
+            ("e", Gate::OR, vec![Node(x), Node(y)]),
+            ("f", Gate::LSHIFT, vec![Node(x), Const(2)]),
+            ("g", Gate::RSHIFT, vec![Node(y), Const(2)]),
+            ("h", Gate::NOT, vec![Node(x)]),
+            ("i", Gate::NOT, vec![Node(y)]),
+        ];
 
-        assert_eq!(Circuit::try_from(INPUT).unwrap().0, expected);
+        assert_eq!(circuit.len(), expected.len());
+        for (wire, op, args) in expected {
+            let id = circuit.id(wire).unwrap();
+            assert_eq!(circuit.expr(id), Some(&Expr { op, args }), "{wire}");
+        }
     }
 
     #[test]
     fn eval_circuit() {
-        use Signal::*;
-        let mut circuit = Circuit::try_from(INPUT).unwrap();
+        let mut circuit = super::parse_circuit(INPUT).unwrap();
         let signals = vec![
-            ("d", Value(72)),
-            ("e", Value(507)),
-            ("f", Value(492)),
-            ("g", Value(114)),
-            ("h", Value(65412)),
-            ("i", Value(65079)),
-            ("x", Value(123)),
-            ("y", Value(456)),
+            ("d", 72),
+            ("e", 507),
+            ("f", 492),
+            ("g", 114),
+            ("h", 65412),
+            ("i", 65079),
+            ("x", 123),
+            ("y", 456),
         ];
         for (wire, expected) in signals {
-            circuit = circuit.eval(wire).unwrap();
-            assert_eq!(circuit[wire], expected);
+            assert_eq!(circuit.eval(wire), Ok(expected), "{wire}");
         }
+
+        // Overriding a wire changes the signals on the wires it's connected to
+        circuit.set_input("x", 0);
+        assert_eq!(circuit.eval("d"), Ok(0));
+        assert_eq!(circuit.eval("e"), Ok(456));
+    }
+
+    #[test]
+    fn loop_circuit() {
+        let mut circuit = super::parse_circuit("a AND 1 -> b\nNOT b -> a").unwrap();
+        assert_eq!(
+            circuit.eval("a"),
+            Err(EvalError::Cycle(vec!["a".into(), "b".into()]))
+        );
     }
 }
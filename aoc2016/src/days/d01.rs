@@ -1,79 +1,40 @@
+use utils::geom::{Dir4, Point2, Segment};
+use utils::viz::{Animation, Cell};
 use utils::PuzzleResult;
 
 pub fn part1(input: &str) -> PuzzleResult {
-    let directions: Vec<(char, i32)> = input
-        .trim()
-        .split(", ")
-        .map(|s| {
-            let direction = s.chars().next().unwrap();
-            let distance = s[1..].parse().unwrap();
+    let directions = parse(input);
 
-            (direction, distance)
-        })
-        .collect();
-
-    let mut position = (0, 0);
-    let mut ordinal = Ordinal::North;
-    for direction in directions {
-        ordinal = match direction.0 {
-            'L' => ordinal.left(),
-            'R' => ordinal.right(),
-            _ => panic!("Invalid input!"),
-        };
-
-        use Ordinal::*;
-        let distance = direction.1;
-        position = match ordinal {
-            North => (position.0, position.1 + distance),
-            South => (position.0, position.1 - distance),
-            East => (position.0 + distance, position.1),
-            West => (position.0 - distance, position.1),
-        };
+    let mut position = Point2::origin();
+    let mut facing = Dir4::North;
+    for (turn, distance) in directions {
+        facing = turn(facing);
+        position = position.step(facing, distance);
     }
 
-    let distance = position.0.abs() + position.1.abs();
+    let distance = position.manhattan(&Point2::origin());
     Ok(format!("{}", distance))
 }
 
 pub fn part2(input: &str) -> PuzzleResult {
-    let directions: Vec<(char, i32)> = input
-        .trim()
-        .split(", ")
-        .map(|s| {
-            let direction = s.chars().next().unwrap();
-            let distance = s[1..].parse().unwrap();
-
-            (direction, distance)
-        })
-        .collect();
+    let directions = parse(input);
 
     // Build the list of all visited points (including points between the endpoints defined by the
     // instructions).
-    let mut visited = vec![(0, 0)];
-    let mut ordinal = Ordinal::North;
-    for direction in directions {
-        ordinal = match direction.0 {
-            'L' => ordinal.left(),
-            'R' => ordinal.right(),
-            _ => panic!("Invalid input!"),
-        };
-
-        use Ordinal::*;
-        let distance = direction.1;
-        let prev_pos = visited.last().unwrap();
-        let next_pos = match ordinal {
-            North => (prev_pos.0, prev_pos.1 + distance),
-            South => (prev_pos.0, prev_pos.1 - distance),
-            East => (prev_pos.0 + distance, prev_pos.1),
-            West => (prev_pos.0 - distance, prev_pos.1),
-        };
-        let line = LineSegment::new(*prev_pos, next_pos);
-        let mut line_points = line.points();
-        visited.append(&mut line_points);
+    let mut visited = vec![Point2::origin()];
+    let mut facing = Dir4::North;
+    for (turn, distance) in directions {
+        facing = turn(facing);
+
+        let prev_pos = *visited.last().unwrap();
+        let next_pos = prev_pos.step(facing, distance);
+        visited.append(&mut path(prev_pos, next_pos));
     }
 
-    // Find the first point in the list that has previously been visited
     let visited = visited; // mark as immutable for multiple concurrent refs
+    animate(&visited);
+
+    // Find the first point in the list that has previously been visited
     let position = visited
         .iter()
         .enumerate()
@@ -87,73 +48,50 @@ pub fn part2(input: &str) -> PuzzleResult {
         .next()
         .unwrap();
 
-    let distance = position.0.abs() + position.1.abs();
+    let distance = position.manhattan(&Point2::origin());
     Ok(format!("{}", distance))
 }
 
-enum Ordinal {
-    North,
-    South,
-    East,
-    West,
-}
-
-impl Ordinal {
-    /// Get the ordinal 90 degrees to the left of this one
-    fn left(&self) -> Ordinal {
-        use Ordinal::*;
-
-        match self {
-            North => West,
-            West => South,
-            South => East,
-            East => North,
-        }
+/// Animate walking the path, marking the start & where we are
+fn animate(path: &[Point2<i32>]) {
+    let mut animation = Animation::new("2016 day 1: walking to Easter Bunny HQ");
+    if !animation.enabled() {
+        return;
     }
 
-    /// Get the ordinal 90 degrees to the right of this one
-    fn right(&self) -> Ordinal {
-        self.left().left().left()
+    let start = (Point2::origin(), Cell::colored('S', [255, 192, 0]));
+    for &position in path {
+        let here = (position, Cell::colored('@', [255, 0, 0]));
+        animation.points([start, here], position);
+        animation.plot(position, Cell::colored('.', [0, 192, 0]));
     }
 }
 
-struct LineSegment {
-    p1: (i32, i32),
-    p2: (i32, i32),
-}
+/// Which way to turn before walking
+type Turn = fn(Dir4) -> Dir4;
 
-impl LineSegment {
-    fn new(p1: (i32, i32), p2: (i32, i32)) -> Self {
-        Self { p1, p2 }
-    }
+/// Parse the instructions into the turn to make and the distance to walk afterwards
+fn parse(input: &str) -> Vec<(Turn, i32)> {
+    input
+        .trim()
+        .split(", ")
+        .map(|s| {
+            let turn = match s.chars().next().unwrap() {
+                'L' => Dir4::left as Turn,
+                'R' => Dir4::right,
+                _ => panic!("Invalid input!"),
+            };
+            let distance = s[1..].parse().unwrap();
 
-    /// Return the points between the two endpoints that define this line segment (including `p2`
-    /// but excluding `p1` since `p1` should already be in the list of visited points).
-    fn points(&self) -> Vec<(i32, i32)> {
-        let (x1, y1) = self.p1;
-        let (x2, y2) = self.p2;
+            (turn, distance)
+        })
+        .collect()
+}
 
-        // Case 1: moving in the +x direction
-        if x1 < x2 {
-            let dist = x2 - x1;
-            (1..=dist).map(|x| (x1 + x, y1)).collect()
-        }
-        // Case 2: moving in the -x direction
-        else if x1 > x2 {
-            let dist = x1 - x2;
-            (1..=dist).map(|x| (x1 - x, y1)).collect()
-        }
-        // Case 3: moving in the +y direction
-        else if y1 < y2 {
-            let dist = y2 - y1;
-            (1..=dist).map(|y| (x1, y1 + y)).collect()
-        }
-        // Case 4: moving in the -y direction
-        else {
-            let dist = y1 - y2;
-            (1..=dist).map(|y| (x1, y1 - y)).collect()
-        }
-    }
+/// Return the points walked through going from `p1` to `p2` (including `p2` but excluding `p1`
+/// since `p1` should already be in the list of visited points).
+fn path(p1: Point2<i32>, p2: Point2<i32>) -> Vec<Point2<i32>> {
+    Segment::new(p1, p2).unwrap().points().skip(1).collect()
 }
 
 #[cfg(test)]
@@ -177,51 +115,43 @@ mod tests {
         assert_eq!(part2(input).unwrap(), format!("{}", expected));
     }
 
-    mod line_segment {
-        use super::*;
-
-        #[test]
-        fn points() {
-            let cases = vec![
-                (
-                    LineSegment::new((0, 0), (8, 0)),
-                    vec![
-                        (1, 0),
-                        (2, 0),
-                        (3, 0),
-                        (4, 0),
-                        (5, 0),
-                        (6, 0),
-                        (7, 0),
-                        (8, 0),
-                    ],
-                ),
-                (
-                    LineSegment::new((8, 0), (8, -4)),
-                    vec![(8, -1), (8, -2), (8, -3), (8, -4)],
-                ),
-                (
-                    LineSegment::new((8, -4), (4, -4)),
-                    vec![(7, -4), (6, -4), (5, -4), (4, -4)],
-                ),
-                (
-                    LineSegment::new((4, -4), (4, 4)),
-                    vec![
-                        (4, -3),
-                        (4, -2),
-                        (4, -1),
-                        (4, 0),
-                        (4, 1),
-                        (4, 2),
-                        (4, 3),
-                        (4, 4),
-                    ],
-                ),
-            ];
-
-            for (input, expected) in cases {
-                assert_eq!(input.points(), expected, "actual != expected");
-            }
+    #[test]
+    fn walk_path() {
+        let cases = vec![
+            (
+                ((0, 0), (8, 0)),
+                vec![
+                    (1, 0),
+                    (2, 0),
+                    (3, 0),
+                    (4, 0),
+                    (5, 0),
+                    (6, 0),
+                    (7, 0),
+                    (8, 0),
+                ],
+            ),
+            (((8, 0), (8, -4)), vec![(8, -1), (8, -2), (8, -3), (8, -4)]),
+            (((8, -4), (4, -4)), vec![(7, -4), (6, -4), (5, -4), (4, -4)]),
+            (
+                ((4, -4), (4, 4)),
+                vec![
+                    (4, -3),
+                    (4, -2),
+                    (4, -1),
+                    (4, 0),
+                    (4, 1),
+                    (4, 2),
+                    (4, 3),
+                    (4, 4),
+                ],
+            ),
+        ];
+
+        for (input, expected) in cases {
+            let expected: Vec<_> = expected.into_iter().map(Point2::from).collect();
+            let (p1, p2) = input;
+            assert_eq!(path(p1.into(), p2.into()), expected, "actual != expected");
         }
     }
 }
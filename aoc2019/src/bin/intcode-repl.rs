@@ -0,0 +1,280 @@
+//! An interactive console for Intcode programs which communicate in ASCII (e.g., the text
+//! adventure from day 25). Program output is written to stdout as text and each line read from
+//! stdin is sent to the program, followed by a newline.
+//!
+//! Lines starting with `!` are commands to the console rather than input; run `!help` to list
+//! them.
+//!
+//! Usage: `cargo run -p aoc2019 --bin intcode-repl -- <program> [script]`, where `script` is a
+//! file of lines to enter before reading from stdin.
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::io::{self, BufRead, Write};
+use std::{env, fs};
+
+use aoc2019::intcode::{Interpreter, State};
+
+const HELP: &str = "Commands:
+  !save <file>          Save the state of the program
+  !load <file>          Load a previously saved state
+  !dump [start] [len]   Dump (part of) the program's memory
+  !replay <file>        Enter each line of a file, as if typed
+  !help                 Show this message
+  !quit                 Exit the console";
+
+/// The number of values on each line of a memory dump
+const DUMP_WIDTH: usize = 10;
+
+struct Repl<W: Write> {
+    program: Interpreter,
+
+    /// Lines to enter before reading from stdin; e.g., from a script being replayed
+    pending: VecDeque<String>,
+
+    /// Whether the program has halted (or failed); only commands are accepted until a new state
+    /// is loaded.
+    halted: bool,
+
+    out: W,
+}
+
+impl<W: Write> Repl<W> {
+    fn new(program: Interpreter, out: W) -> Self {
+        Self {
+            program,
+            pending: VecDeque::new(),
+            halted: false,
+            out,
+        }
+    }
+
+    /// Run the program, reading lines from `stdin` whenever it needs input, until the end of
+    /// `stdin` or a `!quit` command.
+    fn run(&mut self, stdin: &mut impl BufRead) -> Result<(), Box<dyn Error>> {
+        loop {
+            if !self.halted {
+                self.run_until_input()?;
+            }
+
+            let line = match self.pending.pop_front() {
+                Some(line) => {
+                    // Echo entered lines so the transcript reads the same as if they were typed
+                    writeln!(self.out, "{line}")?;
+                    line
+                }
+                None => {
+                    self.out.flush()?;
+                    let mut line = String::new();
+                    if stdin.read_line(&mut line)? == 0 {
+                        return Ok(());
+                    }
+                    line.trim_end_matches(['\r', '\n']).to_string()
+                }
+            };
+
+            if let Some(command) = line.strip_prefix('!') {
+                match self.command(command) {
+                    Ok(true) => {}
+                    Ok(false) => return Ok(()),
+                    Err(e) => writeln!(self.out, "Error: {e}")?,
+                }
+            } else if self.halted {
+                writeln!(
+                    self.out,
+                    "The program has halted; use !load to restore a saved state, or !quit"
+                )?;
+            } else {
+                for b in line.bytes() {
+                    self.program.push_input(i64::from(b));
+                }
+                self.program.push_input(i64::from(b'\n'));
+            }
+        }
+    }
+
+    /// Run the program until it needs more input, writing its output as it goes. Values which
+    /// aren't ASCII (e.g., a puzzle answer) are written as numbers, on their own line.
+    fn run_until_input(&mut self) -> Result<(), Box<dyn Error>> {
+        loop {
+            match self.program.run_until_io() {
+                Ok(State::Output(value)) => match u8::try_from(value) {
+                    Ok(b) if b.is_ascii() => write!(self.out, "{}", char::from(b))?,
+                    _ => writeln!(self.out, "{value}")?,
+                },
+                Ok(State::NeedsInput) => return Ok(()),
+                Ok(State::Halted) => {
+                    self.halted = true;
+                    writeln!(self.out, "[halted after {} steps]", self.program.steps())?;
+                    return Ok(());
+                }
+                Err(e) => {
+                    self.halted = true;
+                    writeln!(self.out, "[error: {e}]")?;
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Run a console command; returns whether to keep going.
+    fn command(&mut self, command: &str) -> Result<bool, Box<dyn Error>> {
+        let mut words = command.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let args: Vec<&str> = words.collect();
+        let path = || {
+            args.first()
+                .copied()
+                .ok_or_else(|| format!("!{name} needs a file"))
+        };
+
+        match name {
+            "save" => {
+                fs::write(path()?, self.program.save_state())?;
+                writeln!(self.out, "Saved state to {}", path()?)?;
+            }
+            "load" => {
+                self.program = Interpreter::load_state(&fs::read_to_string(path()?)?)?;
+                self.halted = false;
+                writeln!(self.out, "Loaded state from {}", path()?)?;
+            }
+            "dump" => {
+                let memory = self.program.memory();
+                let start: usize = args.first().map(|s| s.parse()).transpose()?.unwrap_or(0);
+                let len = args.get(1).map(|s| s.parse()).transpose()?;
+                let end = len.map_or(memory.len(), |len| {
+                    start.saturating_add(len).min(memory.len())
+                });
+
+                for (i, chunk) in memory
+                    .get(start..end)
+                    .unwrap_or_default()
+                    .chunks(DUMP_WIDTH)
+                    .enumerate()
+                {
+                    let values: Vec<_> = chunk.iter().map(|v| v.to_string()).collect();
+                    writeln!(
+                        self.out,
+                        "{:>6}: {}",
+                        start + i * DUMP_WIDTH,
+                        values.join(" ")
+                    )?;
+                }
+            }
+            "replay" => {
+                let script = fs::read_to_string(path()?)?;
+                for line in script.lines().rev() {
+                    self.pending.push_front(line.to_string());
+                }
+            }
+            "help" => writeln!(self.out, "{HELP}")?,
+            "quit" => return Ok(false),
+            _ => writeln!(self.out, "Unknown command: !{name}\n{HELP}")?,
+        }
+
+        Ok(true)
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+    let (program, script) = match &args[1..] {
+        [program] => (program, None),
+        [program, script] => (program, Some(script)),
+        _ => {
+            eprintln!("Usage: {} <program> [script]\n\n{HELP}", args[0]);
+            std::process::exit(1);
+        }
+    };
+
+    let program = Interpreter::parse(&fs::read_to_string(program)?)?;
+    let mut repl = Repl::new(program, io::stdout());
+    if let Some(script) = script {
+        repl.command(&format!("replay {script}"))?;
+    }
+
+    repl.run(&mut io::stdin().lock())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Prompt with `> `, then echo each line back until an empty line, then output the number of
+    /// lines read (as a digit).
+    const ECHO: &str = r#"
+        prompt: out 62
+                out 32
+                add 0 0 -> [len]
+        loop:   in -> [c]
+                out [c]
+                eq [c] 10 -> [newline]
+                jt [newline] eol
+                add [len] 1 -> [len]
+                jt 1 loop
+        eol:    jf [len] end
+                add [n] 1 -> [n]
+                jt 1 prompt
+        end:    out [n]
+                out 10
+                hlt
+        c:      data 0
+        newline:
+                data 0
+        len:    data 0
+        n:      data 48
+    "#;
+
+    /// Run the console with the given stdin, returning everything it output
+    fn session(stdin: &str) -> String {
+        let program = Interpreter::new(aoc2019::intcode::asm::assemble(ECHO).unwrap());
+        let mut repl = Repl::new(program, Vec::new());
+        repl.run(&mut stdin.as_bytes()).unwrap();
+        String::from_utf8(repl.out).unwrap()
+    }
+
+    #[test]
+    fn ascii() {
+        let output = session("hi\nthere\n\n");
+        assert!(output.starts_with("> hi\n> there\n> \n2\n[halted after "));
+
+        // Input is rejected after the program halts, but commands still work
+        let output = session("\nhi\n!quit\n!bogus\n");
+        assert!(output
+            .ends_with("\nThe program has halted; use !load to restore a saved state, or !quit\n"));
+    }
+
+    #[test]
+    fn commands() {
+        let output = session("!dump 2 12\n!dump 1000\n!dump 1000 18446744073709551615\n!bogus\n");
+        assert_eq!(
+            output,
+            format!(
+                "> {:>6}: 104 32 1101 0 0 43 3 41 4 41\n{:>6}: 1008 41\nUnknown command: !bogus\n{HELP}\n",
+                2, 12
+            )
+        );
+
+        let dir = env::temp_dir().join(format!("intcode-repl-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let state = dir.join("state");
+        let script = dir.join("script");
+        fs::write(&script, format!("a\n!save {}\nb\n\n", state.display())).unwrap();
+
+        // Replay a script (the program echoes each line, as well as the console), then go back to the state it saved and carry on differently
+        let output = session(&format!(
+            "!replay {}\n!load {}\n\n",
+            script.display(),
+            state.display()
+        ));
+        let expected = format!(
+            "> a\na\n> !save {0}\nSaved state to {0}\nb\nb\n> \n\n2\n",
+            state.display()
+        );
+        assert!(output.starts_with(&expected), "{output}");
+        let expected = format!("Loaded state from {}\n\n1\n", state.display());
+        assert!(output.contains(&expected), "{output}");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,10 +1,10 @@
-use crate::intcode::Interpreter;
+use aoc2019::intcode::Interpreter;
 use utils::PuzzleResult;
 
 pub fn part1(input: &str) -> PuzzleResult {
     let mut program = input
         .split(",")
-        .map(|d| d.trim().parse::<u32>())
+        .map(|d| d.trim().parse::<i64>())
         .collect::<Result<Vec<_>, _>>()?;
 
     program[1] = 12;
@@ -18,19 +18,15 @@ pub fn part1(input: &str) -> PuzzleResult {
 }
 
 pub fn part2(input: &str) -> PuzzleResult {
-    let program = input
-        .split(",")
-        .map(|d| d.trim().parse::<u32>())
-        .collect::<Result<Vec<_>, _>>()?;
+    let mut program = Interpreter::parse(input)?;
+    let snapshot = program.snapshot();
 
     let expected = 19690720;
     for noun in 0..=99 {
         for verb in 0..=99 {
-            let mut program = program.clone();
-            program[1] = noun;
-            program[2] = verb;
-
-            let mut program = Interpreter::new(program);
+            program.restore(&snapshot);
+            program.set(1, noun)?;
+            program.set(2, verb)?;
             program.run_to_halt()?;
 
             if program[0] == expected {
@@ -0,0 +1,342 @@
+//! A small assembler to write Intcode programs (mostly for tests) without counting addresses by
+//! hand.
+//!
+//! The syntax is the same as the output of the [disassembler](super::disasm), so the two
+//! round-trip; i.e., `assemble(&disassemble(tape))` gives back `tape`. Each line holds at most one
+//! instruction, made up of a (case-insensitive) mnemonic followed by its parameters:
+//!
+//! - `[9]` is a [`Position`](Mode::Position) parameter
+//! - `9` is an [`Immediate`](Mode::Immediate) parameter
+//! - `[rb+9]` or `[rb-9]` is a [`Relative`](Mode::Relative) parameter
+//!
+//! Parameters may be separated by whitespace or commas, and an `->` may be written before the
+//! parameter an instruction writes to. `DATA` takes any number of (immediate) values and places
+//! them directly on the tape.
+//!
+//! A line may start with a label, like `loop:`, which can then be used as a value in place of a
+//! number; e.g., `JT 1 loop` or `ADD [x] 1 -> [x]`. Labels may be offset, like `[x+1]`. A numeric
+//! label, like `4:`, is an address annotation instead; it is checked against the actual address.
+//!
+//! Comments start with `;` and run to the end of the line.
+
+use std::collections::HashMap;
+use std::error::Error;
+
+use super::{Mode, Opcode};
+
+/// A value in the source: either a number or a (possibly offset) label
+#[derive(Debug, Clone)]
+enum Value {
+    Number(i64),
+    Label(String, i64),
+}
+
+/// A single instruction parameter
+#[derive(Debug, Clone)]
+struct Param {
+    mode: Mode,
+    value: Value,
+}
+
+/// A single line of source, after labels and comments have been removed
+#[derive(Debug)]
+enum Statement {
+    Instruction(Opcode, Vec<Param>),
+    Data(Vec<Value>),
+}
+
+impl Statement {
+    /// The number of values this statement places on the tape
+    fn len(&self) -> usize {
+        match self {
+            Self::Instruction(opcode, _) => 1 + opcode.num_params(),
+            Self::Data(values) => values.len(),
+        }
+    }
+}
+
+/// Assemble a program into an Intcode tape.
+pub fn assemble(source: &str) -> Result<Vec<i64>, Box<dyn Error>> {
+    let mut labels: HashMap<String, i64> = HashMap::new();
+    let mut statements = Vec::new();
+    let mut addr = 0;
+
+    // First pass: parse each line and find the address of each label
+    for (i, line) in source.lines().enumerate() {
+        let err = |msg: String| -> Box<dyn Error> { format!("Line {}: {msg}", i + 1).into() };
+
+        let mut line = line.split(';').next().unwrap_or_default().trim();
+        while let Some((label, rest)) = line.split_once(':') {
+            let label = label.trim();
+            if let Ok(n) = label.parse::<usize>() {
+                if n != addr {
+                    return Err(err(format!("Expected address {n}, but it is {addr}")));
+                }
+            } else if is_label(label) {
+                if labels.insert(label.to_string(), addr as i64).is_some() {
+                    return Err(err(format!("Duplicate label: {label}")));
+                }
+            } else {
+                return Err(err(format!("Invalid label: {label}")));
+            }
+
+            line = rest.trim();
+        }
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let statement = parse_statement(line).map_err(err)?;
+        addr += statement.len();
+        statements.push((i, statement));
+    }
+
+    // Second pass: encode each statement now all of the labels are known
+    let mut tape = Vec::with_capacity(addr);
+    for (i, statement) in statements {
+        let err = |msg: String| -> Box<dyn Error> { format!("Line {}: {msg}", i + 1).into() };
+        let resolve = |value: &Value| match value {
+            Value::Number(n) => Ok(*n),
+            Value::Label(label, offset) => labels
+                .get(label)
+                .map(|addr| addr + offset)
+                .ok_or_else(|| err(format!("Unknown label: {label}"))),
+        };
+
+        match statement {
+            Statement::Instruction(opcode, params) => {
+                let instruction = params
+                    .iter()
+                    .enumerate()
+                    .map(|(n, param)| mode_digit(param.mode) * 10_i64.pow(n as u32 + 2))
+                    .sum::<i64>()
+                    + opcode.code();
+
+                tape.push(instruction);
+                for param in &params {
+                    tape.push(resolve(&param.value)?);
+                }
+            }
+            Statement::Data(values) => {
+                for value in &values {
+                    tape.push(resolve(value)?);
+                }
+            }
+        }
+    }
+
+    Ok(tape)
+}
+
+/// Parse a single instruction (without any labels or comments)
+fn parse_statement(line: &str) -> Result<Statement, String> {
+    let mut tokens = line
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|t| !t.is_empty());
+    let mnemonic = tokens.next().unwrap_or_default();
+
+    // Note where the `->` is (if there is one), to check it's before the written parameter
+    let mut arrow = None;
+    let mut params = Vec::new();
+    for token in tokens {
+        if token == "->" {
+            arrow = Some(params.len());
+        } else {
+            params.push(token);
+        }
+    }
+
+    if mnemonic.eq_ignore_ascii_case("DATA") {
+        let values = params
+            .iter()
+            .map(|p| parse_value(p))
+            .collect::<Result<_, _>>()?;
+        return Ok(Statement::Data(values));
+    }
+
+    let opcode = Opcode::from_mnemonic(mnemonic)
+        .ok_or_else(|| format!("Unknown instruction: {mnemonic}"))?;
+    let num_params = opcode.num_params();
+
+    if params.len() != num_params {
+        return Err(format!(
+            "{mnemonic} takes {num_params} parameters, but {} were given",
+            params.len()
+        ));
+    }
+    if arrow.is_some() && (!opcode.writes() || arrow != Some(num_params - 1)) {
+        return Err(format!("Misplaced `->` in {line}"));
+    }
+
+    let params = params
+        .iter()
+        .map(|p| parse_param(p))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if opcode.writes() && params[num_params - 1].mode == Mode::Immediate {
+        return Err(format!(
+            "The last parameter of {mnemonic} must be an address"
+        ));
+    }
+
+    Ok(Statement::Instruction(opcode, params))
+}
+
+/// Parse a parameter like `[9]`, `9`, or `[rb-9]`
+fn parse_param(s: &str) -> Result<Param, String> {
+    let Some(inner) = s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) else {
+        return Ok(Param {
+            mode: Mode::Immediate,
+            value: parse_value(s)?,
+        });
+    };
+
+    if let Some(offset) = inner
+        .strip_prefix("rb")
+        .filter(|o| !o.starts_with(is_label_char))
+    {
+        let offset = match offset {
+            "" => 0,
+            _ => offset
+                .strip_prefix('+')
+                .unwrap_or(offset)
+                .parse()
+                .map_err(|_| format!("Invalid relative parameter: {s}"))?,
+        };
+
+        return Ok(Param {
+            mode: Mode::Relative,
+            value: Value::Number(offset),
+        });
+    }
+
+    Ok(Param {
+        mode: Mode::Position,
+        value: parse_value(inner)?,
+    })
+}
+
+/// Parse a value like `-9`, `loop`, or `x+1`
+fn parse_value(s: &str) -> Result<Value, String> {
+    if let Ok(n) = s.parse() {
+        return Ok(Value::Number(n));
+    }
+
+    let (label, offset) = match s.find(['+', '-']) {
+        Some(i) => {
+            let offset = s[i..].strip_prefix('+').unwrap_or(&s[i..]);
+            let offset = offset.parse().map_err(|_| format!("Invalid value: {s}"))?;
+            (&s[..i], offset)
+        }
+        None => (s, 0),
+    };
+
+    if !is_label(label) {
+        return Err(format!("Invalid value: {s}"));
+    }
+
+    Ok(Value::Label(label.to_string(), offset))
+}
+
+/// Whether a string is a valid label name; `rb` is reserved for relative parameters
+fn is_label(s: &str) -> bool {
+    let mut chars = s.chars();
+    let starts_ok = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+
+    starts_ok && chars.all(is_label_char) && s != "rb"
+}
+
+fn is_label_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// The digit representing a parameter mode in an instruction
+fn mode_digit(mode: Mode) -> i64 {
+    match mode {
+        Mode::Position => 0,
+        Mode::Immediate => 1,
+        Mode::Relative => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::disasm::disassemble;
+    use super::*;
+
+    #[test]
+    fn instructions() {
+        let source = r#"
+            ; From day 2
+            ADD [9] [10] -> [3]
+            mul [3], [11], [0]
+            hlt
+            data 30 40 50
+        "#;
+        assert_eq!(
+            assemble(source).unwrap(),
+            vec![1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50]
+        );
+
+        let source = "IN -> [rb-1]\nLT 3 -8 -> [rb+0]\nARB 19\nOUT 1125899906842624";
+        assert_eq!(
+            assemble(source).unwrap(),
+            vec![203, -1, 21107, 3, -8, 0, 109, 19, 104, 1125899906842624]
+        );
+    }
+
+    #[test]
+    fn labels() {
+        let source = r#"
+            start: in -> [x]
+                   jf [x] end     ; stop at zero
+                   mul [x] 2 -> [x+1]
+                   out [x+1]
+                   jt 1 start
+            end:   hlt
+            x:     data 0 0
+        "#;
+        assert_eq!(
+            assemble(source).unwrap(),
+            vec![3, 15, 1006, 15, 14, 1002, 15, 2, 16, 4, 16, 1105, 1, 0, 99, 0, 0]
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let tape = vec![
+            3, 21, 1008, 21, 8, 20, 1005, 20, 22, 107, 8, 21, 20, 1006, 20, 31, 1106, 0, 36, 98, 0,
+            0, 1002, 21, 125, 20, 4, 20, 1105, 1, 46, 104, 999, 1105, 1, 46, 1101, 1000, 1, 20, 4,
+            20, 1105, 1, 46, 98, 99,
+        ];
+        assert_eq!(assemble(&disassemble(&tape)).unwrap(), tape);
+
+        let quine = vec![
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+        ];
+        assert_eq!(assemble(&disassemble(&quine)).unwrap(), quine);
+    }
+
+    #[test]
+    fn errors() {
+        let cases = vec![
+            "nop",
+            "add [1] [2]",
+            "add [1] [2] -> 3",
+            "out -> [1]",
+            "add [1] -> [2] [3]",
+            "jt 1 nowhere",
+            "x: hlt\nx: hlt",
+            "4: hlt",
+            "out [rb+x]",
+            "1x: hlt",
+            "rb: hlt",
+        ];
+        for source in cases {
+            assert!(assemble(source).is_err(), "{source}");
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Render Intcode programs as (roughly) human-readable assembly.
+//!
+//! Each instruction is rendered as its mnemonic followed by its parameters, which are shown
+//! according to their [Mode]:
+//!
+//! - [`Position`](Mode::Position) parameters as the address in brackets, e.g. `[9]`
+//! - [`Immediate`](Mode::Immediate) parameters as the bare value, e.g. `9`
+//! - [`Relative`](Mode::Relative) parameters as an offset from the relative base, e.g. `[rb+9]`
+//!
+//! Parameters which are written to are shown after an arrow, e.g. `ADD [9] [10] -> [3]`.
+
+use super::{Instruction, Mode};
+
+/// Disassemble a whole tape, one instruction per line, with each line prefixed by its address.
+///
+/// Intcode doesn't separate code from data, so this decodes instructions one after the other from
+/// the start of the tape; any value which isn't a valid instruction is shown as `DATA`. Data which
+/// happens to look like an instruction (e.g., the `1` in `99,1,2,3`) will be shown as one.
+pub fn disassemble(tape: &[i64]) -> String {
+    let mut out = String::new();
+    let mut addr = 0;
+
+    while addr < tape.len() {
+        let (text, len) = match instruction(tape, addr) {
+            Some((text, len)) if addr + len <= tape.len() => (text, len),
+            _ => (format!("DATA {}", tape[addr]), 1),
+        };
+
+        out.push_str(&format!("{addr:>5}: {text}\n"));
+        addr += len;
+    }
+
+    out
+}
+
+/// Disassemble the single instruction at `addr`, returning its text and its length (including its
+/// parameters). Memory past the end of the tape is treated as zeroes, as it is by the
+/// [Interpreter](super::Interpreter).
+///
+/// Returns `None` if the value at `addr` isn't a valid instruction. This includes values with
+/// modes set for parameters the operation doesn't have (e.g., `10099`); the interpreter ignores
+/// these, but they would not survive a round-trip through the [assembler](super::asm).
+pub fn instruction(tape: &[i64], addr: usize) -> Option<(String, usize)> {
+    let ins = Instruction::decode(addr, |addr| Ok(tape.get(addr).copied().unwrap_or(0))).ok()?;
+    let num_params = ins.opcode.num_params();
+    if tape.get(addr).copied().unwrap_or(0) / 10_i64.pow(num_params as u32 + 2) != 0 {
+        return None;
+    }
+
+    Some((render(&ins), 1 + num_params))
+}
+
+/// Render an already decoded instruction
+pub(super) fn render(ins: &Instruction) -> String {
+    let num_params = ins.opcode.num_params();
+
+    let mut text = ins.opcode.mnemonic().to_string();
+    for (n, p) in ins.params().iter().enumerate() {
+        if ins.opcode.writes() && n + 1 == num_params {
+            text.push_str(" ->");
+        }
+        text.push(' ');
+        text.push_str(&param(p.mode, p.raw));
+    }
+
+    text
+}
+
+/// Render a single parameter according to its mode
+fn param(mode: Mode, raw: i64) -> String {
+    match mode {
+        Mode::Position => format!("[{raw}]"),
+        Mode::Immediate => raw.to_string(),
+        Mode::Relative if raw < 0 => format!("[rb{raw}]"),
+        Mode::Relative => format!("[rb+{raw}]"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instructions() {
+        let cases = vec![
+            (vec![1, 9, 10, 3], "ADD [9] [10] -> [3]"),
+            (vec![1002, 4, 3, 4], "MUL [4] 3 -> [4]"),
+            (vec![203, -1], "IN -> [rb-1]"),
+            (vec![104, 1125899906842624], "OUT 1125899906842624"),
+            (vec![1105, 1, 9], "JT 1 9"),
+            (vec![1006, 20, 31], "JF [20] 31"),
+            (vec![21107, 3, 8, 0], "LT 3 8 -> [rb+0]"),
+            (vec![8, 9, 10, 9], "EQ [9] [10] -> [9]"),
+            (vec![109, 19], "ARB 19"),
+            (vec![99], "HLT"),
+        ];
+
+        for (tape, expected) in cases {
+            let (text, len) = instruction(&tape, 0).unwrap();
+            assert_eq!(text, expected);
+            assert_eq!(len, tape.len());
+        }
+
+        // Unknown opcode, unknown mode, and writing to an immediate parameter
+        assert!(instruction(&[42], 0).is_none());
+        assert!(instruction(&[301, 0, 0, 0], 0).is_none());
+        assert!(instruction(&[11101, 1, 1, 5], 0).is_none());
+        assert!(instruction(&[10099], 0).is_none());
+    }
+
+    #[test]
+    fn whole_tape() {
+        let tape = vec![1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50];
+        let expected = r#"    0: ADD [9] [10] -> [3]
+    4: MUL [3] [11] -> [0]
+    8: HLT
+    9: DATA 30
+   10: DATA 40
+   11: DATA 50
+"#;
+
+        assert_eq!(disassemble(&tape), expected);
+
+        // An instruction whose parameters run past the end of the tape
+        assert_eq!(disassemble(&[1, 0]), "    0: DATA 1\n    1: DATA 0\n");
+    }
+}
@@ -0,0 +1,90 @@
+//! The ways in which running an Intcode program can fail
+
+use std::error::Error;
+use std::fmt;
+
+/// An error raised while running an Intcode program. Where there is one, the address of the
+/// instruction which caused the error is recorded (`addr`).
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntcodeError {
+    /// The instruction's operation isn't one the interpreter knows about
+    UnknownOpcode { addr: usize, value: i64 },
+
+    /// The `param`th (1-indexed) parameter of the instruction has an unknown mode
+    BadMode {
+        addr: usize,
+        param: usize,
+        mode: i64,
+    },
+
+    /// The `param`th (1-indexed) parameter of the instruction is written to, but is in
+    /// [`Immediate`](super::Mode::Immediate) mode
+    ImmediateWrite { addr: usize, param: usize },
+
+    /// The instruction tried to use a negative value as an address
+    NegativeAddress { addr: usize, value: i64 },
+
+    /// The instruction tried to access memory past the end of the tape, which isn't allowed with
+    /// [`Memory::Fault`](super::Memory::Fault)
+    OutOfBounds { addr: usize, target: usize },
+
+    /// The instruction tried to grow memory past [MAX_MEMORY](super::MAX_MEMORY) values, by
+    /// accessing `target`
+    MemoryLimit { addr: usize, target: usize },
+
+    /// The instruction's arithmetic (including working out a relative address) overflowed
+    Overflow { addr: usize },
+
+    /// The instruction needs input, but there is none available
+    NoInput { addr: usize },
+
+    /// The interpreter reached its step limit before executing the instruction
+    StepLimit { addr: usize, limit: usize },
+
+    /// Every running machine in a [FeedbackLoop](super::FeedbackLoop) is waiting for input which
+    /// will never arrive
+    Deadlock,
+}
+
+impl fmt::Display for IntcodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownOpcode { addr, value } => {
+                write!(f, "Unknown opcode {value} at {addr}")
+            }
+            Self::BadMode { addr, param, mode } => write!(
+                f,
+                "Unknown mode {mode} for parameter {param} of instruction at {addr}"
+            ),
+            Self::ImmediateWrite { addr, param } => write!(
+                f,
+                "Parameter {param} of instruction at {addr} is an address; it cannot be immediate"
+            ),
+            Self::NegativeAddress { addr, value } => {
+                write!(f, "Negative address {value} used by instruction at {addr}")
+            }
+            Self::OutOfBounds { addr, target } => write!(
+                f,
+                "Address {target} used by instruction at {addr} is past the end of memory"
+            ),
+            Self::MemoryLimit { addr, target } => write!(
+                f,
+                "Address {target} used by instruction at {addr} is past the memory limit of {}",
+                super::MAX_MEMORY
+            ),
+            Self::Overflow { addr } => {
+                write!(f, "Arithmetic overflow in instruction at {addr}")
+            }
+            Self::NoInput { addr } => {
+                write!(f, "No input available for instruction at {addr}")
+            }
+            Self::StepLimit { addr, limit } => write!(
+                f,
+                "Step limit of {limit} reached at instruction {addr}; is the program stuck in a loop?"
+            ),
+            Self::Deadlock => write!(f, "Deadlock: every running machine is waiting for input"),
+        }
+    }
+}
+
+impl Error for IntcodeError {}
@@ -0,0 +1,120 @@
+//! Run several [Interpreter]s connected in a loop, with each machine's output feeding into the
+//! next machine's input (and the last machine's output feeding back into the first).
+
+use super::{IntcodeError, Interpreter, State};
+
+/// A chain of Intcode machines connected in a loop
+#[derive(Debug, Clone)]
+pub struct FeedbackLoop {
+    machines: Vec<Interpreter>,
+}
+
+impl FeedbackLoop {
+    /// Connect the given machines in a loop, in order. Any input the machines need before the
+    /// loop starts (e.g., phase settings) should already be queued.
+    pub fn new(machines: Vec<Interpreter>) -> Self {
+        Self { machines }
+    }
+
+    /// Send `input` to the first machine, then run the machines round-robin until all of them
+    /// have halted. Returns the last value output by the final machine, if there was one.
+    pub fn run(&mut self, input: i64) -> Result<Option<i64>, IntcodeError> {
+        let n = self.machines.len();
+        if n == 0 {
+            return Ok(None);
+        }
+
+        self.machines[0].push_input(input);
+
+        let mut halted = vec![false; n];
+        let mut last = None;
+
+        loop {
+            // Whether any machine output a value or halted during this pass
+            let mut progressed = false;
+
+            for (i, done) in halted.iter_mut().enumerate() {
+                if *done {
+                    continue;
+                }
+
+                loop {
+                    match self.machines[i].run_until_io()? {
+                        State::Output(value) => {
+                            progressed = true;
+                            if i == n - 1 {
+                                last = Some(value);
+                            }
+                            self.machines[(i + 1) % n].push_input(value);
+                        }
+                        State::NeedsInput => break,
+                        State::Halted => {
+                            progressed = true;
+                            *done = true;
+                            break;
+                        }
+                    }
+                }
+            }
+
+            if halted.iter().all(|&h| h) {
+                return Ok(last);
+            }
+
+            // If nothing was output, every running machine is still waiting on an empty input
+            // queue, and nothing will ever fill them.
+            if !progressed {
+                return Err(IntcodeError::Deadlock);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Set up one amplifier per phase setting, all running the given program
+    fn amplifiers(program: &str, phases: &[i64]) -> FeedbackLoop {
+        let machines = phases
+            .iter()
+            .map(|&phase| {
+                let mut machine = Interpreter::parse(program).unwrap();
+                machine.push_input(phase);
+                machine
+            })
+            .collect();
+
+        FeedbackLoop::new(machines)
+    }
+
+    #[test]
+    fn chain() {
+        // From 2019 day 7, part 1; the last output isn't fed back since the first machine has
+        // already halted.
+        let program = "3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0";
+        let mut amps = amplifiers(program, &[4, 3, 2, 1, 0]);
+        assert_eq!(amps.run(0).unwrap(), Some(43210));
+    }
+
+    #[test]
+    fn feedback() {
+        // From 2019 day 7, part 2
+        let program =
+            "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5";
+        let mut amps = amplifiers(program, &[9, 8, 7, 6, 5]);
+        assert_eq!(amps.run(0).unwrap(), Some(139629729));
+    }
+
+    #[test]
+    fn deadlock() {
+        // Each machine reads two values before outputting anything
+        let program = "3,9,3,9,4,9,99,0,0,0";
+        let mut amps = amplifiers(program, &[]);
+        assert_eq!(amps.run(0), Ok(None));
+
+        let machines = vec![Interpreter::parse(program).unwrap(); 2];
+        let mut amps = FeedbackLoop::new(machines);
+        assert_eq!(amps.run(0), Err(IntcodeError::Deadlock));
+    }
+}
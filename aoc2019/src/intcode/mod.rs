@@ -1,158 +1,660 @@
 //! Module for a re-usable Intcode interpreter & assorted helper types
 
+use std::collections::VecDeque;
+
+pub mod asm;
+pub mod disasm;
+mod error;
+mod feedback;
+mod state;
+mod tape;
+mod trace;
+
+pub use error::IntcodeError;
+pub use feedback::FeedbackLoop;
+pub use trace::TraceEntry;
+
+use tape::Tape;
+
 /// Valid operations the Intcode interpreter can perform
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 enum Opcode {
-    /// 1  - Add together numbers read from the positions specified by `ins + 1` and `ins + 2`,
-    ///      then write the result to the position specified by `ins + 3`, then increment the
-    ///      instruction pointer.
+    /// 1  - Add together the first two parameters, then write the result to the position
+    ///      specified by the third parameter.
     Add,
 
-    /// 2  - Multiply together numbers read from the positions specified by `ins + 1` and `ins +
-    ///      2`, then write the result to the position specified by `ins + 3`, then increment the
-    ///      instruction pointer.
+    /// 2  - Multiply together the first two parameters, then write the result to the position
+    ///      specified by the third parameter.
     Mul,
 
+    /// 3  - Take a single value from the input and write it to the position specified by the
+    ///      only parameter.
+    Input,
+
+    /// 4  - Output the value of the only parameter.
+    Output,
+
+    /// 5  - If the first parameter is non-zero, set the instruction pointer to the value of the
+    ///      second parameter; otherwise, do nothing.
+    JumpIfTrue,
+
+    /// 6  - If the first parameter is zero, set the instruction pointer to the value of the
+    ///      second parameter; otherwise, do nothing.
+    JumpIfFalse,
+
+    /// 7  - If the first parameter is less than the second parameter, write `1` to the position
+    ///      specified by the third parameter; otherwise, write `0`.
+    LessThan,
+
+    /// 8  - If the first parameter is equal to the second parameter, write `1` to the position
+    ///      specified by the third parameter; otherwise, write `0`.
+    Equals,
+
+    /// 9  - Adjust the relative base by the value of the only parameter.
+    AdjustRelativeBase,
+
     /// 99 - Immediately halt program execution
     Halt,
 }
 
-impl TryFrom<u32> for Opcode {
+impl Opcode {
+    /// The number of parameters this operation takes
+    const fn num_params(&self) -> usize {
+        use Opcode::*;
+        match self {
+            Add | Mul | LessThan | Equals => 3,
+            JumpIfTrue | JumpIfFalse => 2,
+            Input | Output | AdjustRelativeBase => 1,
+            Halt => 0,
+        }
+    }
+
+    /// Whether the last parameter of this operation is an address to write to
+    const fn writes(&self) -> bool {
+        use Opcode::*;
+        matches!(self, Add | Mul | Input | LessThan | Equals)
+    }
+
+    /// The numeric code of this operation, i.e. the two lowest digits of an instruction
+    const fn code(&self) -> i64 {
+        use Opcode::*;
+        match self {
+            Add => 1,
+            Mul => 2,
+            Input => 3,
+            Output => 4,
+            JumpIfTrue => 5,
+            JumpIfFalse => 6,
+            LessThan => 7,
+            Equals => 8,
+            AdjustRelativeBase => 9,
+            Halt => 99,
+        }
+    }
+
+    /// Get the operation with the given (case-insensitive) mnemonic; see [`mnemonic`](Self::mnemonic).
+    fn from_mnemonic(mnemonic: &str) -> Option<Self> {
+        use Opcode::*;
+        [
+            Add,
+            Mul,
+            Input,
+            Output,
+            JumpIfTrue,
+            JumpIfFalse,
+            LessThan,
+            Equals,
+            AdjustRelativeBase,
+            Halt,
+        ]
+        .into_iter()
+        .find(|op| op.mnemonic().eq_ignore_ascii_case(mnemonic))
+    }
+
+    /// The short name of this operation used by the [disassembler](disasm) and
+    /// [assembler](asm)
+    const fn mnemonic(&self) -> &'static str {
+        use Opcode::*;
+        match self {
+            Add => "ADD",
+            Mul => "MUL",
+            Input => "IN",
+            Output => "OUT",
+            JumpIfTrue => "JT",
+            JumpIfFalse => "JF",
+            LessThan => "LT",
+            Equals => "EQ",
+            AdjustRelativeBase => "ARB",
+            Halt => "HLT",
+        }
+    }
+}
+
+impl TryFrom<i64> for Opcode {
     type Error = String;
 
-    fn try_from(value: u32) -> Result<Self, Self::Error> {
-        match value {
+    /// Get the operation from an instruction; only the two lowest decimal digits specify the
+    /// operation, the rest specify the [Mode]s of its parameters.
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        match value % 100 {
             1 => Ok(Self::Add),
             2 => Ok(Self::Mul),
+            3 => Ok(Self::Input),
+            4 => Ok(Self::Output),
+            5 => Ok(Self::JumpIfTrue),
+            6 => Ok(Self::JumpIfFalse),
+            7 => Ok(Self::LessThan),
+            8 => Ok(Self::Equals),
+            9 => Ok(Self::AdjustRelativeBase),
             99 => Ok(Self::Halt),
             _ => Err(format!("Unknown opcode: {value}")),
         }
     }
 }
 
+/// The ways in which a parameter to an instruction may be interpreted
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Mode {
+    /// 0 - The parameter is the address of the value to use
+    Position,
+
+    /// 1 - The parameter is the value to use
+    Immediate,
+
+    /// 2 - The parameter, plus the relative base, is the address of the value to use
+    Relative,
+}
+
+impl TryFrom<i64> for Mode {
+    type Error = String;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Position),
+            1 => Ok(Self::Immediate),
+            2 => Ok(Self::Relative),
+            _ => Err(format!("Unknown parameter mode: {value}")),
+        }
+    }
+}
+
+/// A decoded instruction: its operation, along with the mode and raw value of each parameter
+#[derive(Debug, Clone, Copy)]
+struct Instruction {
+    /// The address of the instruction
+    addr: usize,
+    opcode: Opcode,
+
+    /// The instruction's parameters; only the first [`num_params`](Opcode::num_params) are used
+    params: [Param; 3],
+}
+
+/// A single (undecoded) instruction parameter
+#[derive(Debug, Clone, Copy)]
+struct Param {
+    mode: Mode,
+    raw: i64,
+}
+
+impl Instruction {
+    /// Decode the instruction at `addr`, using `read` to read memory. This checks that the
+    /// operation and the modes of its parameters are valid, including that any parameter which is
+    /// written to isn't [`Immediate`](Mode::Immediate).
+    fn decode(
+        addr: usize,
+        read: impl Fn(usize) -> Result<i64, IntcodeError>,
+    ) -> Result<Self, IntcodeError> {
+        let value = read(addr)?;
+        let opcode =
+            Opcode::try_from(value).map_err(|_| IntcodeError::UnknownOpcode { addr, value })?;
+
+        let mut params = [Param {
+            mode: Mode::Position,
+            raw: 0,
+        }; 3];
+        for n in 1..=opcode.num_params() {
+            let digit = value / 10_i64.pow(n as u32 + 1) % 10;
+            let mode = Mode::try_from(digit).map_err(|_| IntcodeError::BadMode {
+                addr,
+                param: n,
+                mode: digit,
+            })?;
+            if mode == Mode::Immediate && opcode.writes() && n == opcode.num_params() {
+                return Err(IntcodeError::ImmediateWrite { addr, param: n });
+            }
+
+            params[n - 1] = Param {
+                mode,
+                raw: read(addr + n)?,
+            };
+        }
+
+        Ok(Self {
+            addr,
+            opcode,
+            params,
+        })
+    }
+
+    /// The parameters the operation actually takes
+    fn params(&self) -> &[Param] {
+        &self.params[..self.opcode.num_params()]
+    }
+
+    /// The address of the next instruction, if this one doesn't jump
+    fn next_addr(&self) -> usize {
+        self.addr + 1 + self.opcode.num_params()
+    }
+}
+
+/// The most values memory can hold when it [grows](Memory::Grow); a program accessing memory past
+/// this is almost certainly broken, rather than really needing terabytes of memory.
+pub const MAX_MEMORY: usize = 1 << 24;
+
+/// How the interpreter treats memory past the end of the initial tape
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum Memory {
+    /// Reading past the end of the tape gives `0`, and writing past it grows the tape (up to
+    /// [MAX_MEMORY] values); this is the behaviour the puzzles (from day 9 on) expect.
+    #[default]
+    Grow,
+
+    /// Any access past the end of the tape is an [`OutOfBounds`](IntcodeError::OutOfBounds)
+    /// error; useful to catch bugs in programs that shouldn't need extra memory.
+    Fault,
+}
+
+/// The reasons [`run_until_io`](Interpreter::run_until_io) may return control to its caller
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum State {
+    /// The next instruction needs input, but the input queue is empty. The instruction has not
+    /// been run; it will be once input is available.
+    NeedsInput,
+
+    /// The program output a value
+    Output(i64),
+
+    /// The program has halted
+    Halted,
+}
+
+/// A saved copy of an [Interpreter]'s state; see [`snapshot`](Interpreter::snapshot)
+#[derive(Debug, Clone)]
+pub struct Snapshot(Interpreter);
+
 /// A re-usable Intcode interpreter to use across several days' puzzles
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Interpreter {
-    /// The underlying tape of instructions, arguments, etc.
-    tape: Vec<u32>,
+    /// The underlying tape of instructions, arguments, etc. With [`Memory::Grow`], this grows as
+    /// needed when the program writes past the end of it. Its pages are shared with any clones
+    /// (e.g., [Snapshot]s) until written to.
+    tape: Tape,
+
+    /// How to treat accesses past the end of the tape
+    memory: Memory,
 
     /// The instruction pointer for the interpreter to use when processing the tape
     ins_ptr: usize,
+
+    /// The base address for parameters in [`Relative`](Mode::Relative) mode
+    relative_base: i64,
+
+    /// Values waiting to be read by [`Input`](Opcode::Input) instructions
+    input: VecDeque<i64>,
+
+    /// Values written by [`Output`](Opcode::Output) instructions
+    output: VecDeque<i64>,
+
+    /// The instructions executed so far, if tracing is enabled
+    trace: Option<Vec<TraceEntry>>,
+
+    /// The number of instructions executed so far
+    steps: usize,
+
+    /// The maximum number of instructions to execute before giving up, if any
+    step_limit: Option<usize>,
 }
 
 impl Interpreter {
-    pub fn new(tape: Vec<u32>) -> Self {
-        Self { tape, ins_ptr: 0 }
+    pub fn new(tape: Vec<i64>) -> Self {
+        Self {
+            tape: Tape::from(tape),
+            memory: Memory::default(),
+            ins_ptr: 0,
+            relative_base: 0,
+            input: VecDeque::new(),
+            output: VecDeque::new(),
+            trace: None,
+            steps: 0,
+            step_limit: None,
+        }
     }
 
-    /// Run the interpreter until the program halts
-    pub fn run_to_halt(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let mut opcode = self.next()?;
+    /// Parse a program from its comma-separated representation (i.e., a puzzle input).
+    pub fn parse(program: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let tape = program
+            .split(",")
+            .map(|d| d.trim().parse::<i64>())
+            .collect::<Result<Vec<_>, _>>()?;
 
-        while opcode != Opcode::Halt {
-            opcode = self.next()?;
-        }
+        Ok(Self::new(tape))
+    }
+
+    /// Set how to treat memory past the end of the tape; see [Memory].
+    pub fn set_memory(&mut self, memory: Memory) {
+        self.memory = memory;
+    }
+
+    /// Add a value to the end of the input queue
+    pub fn push_input(&mut self, value: i64) {
+        self.input.push_back(value);
+    }
+
+    /// Take the oldest value from the output queue
+    pub fn pop_output(&mut self) -> Option<i64> {
+        self.output.pop_front()
+    }
+
+    /// Take all the values from the output queue, oldest first
+    pub fn drain_output(&mut self) -> Vec<i64> {
+        self.output.drain(..).collect()
+    }
+
+    /// Get the queue of values waiting to be read by the program
+    pub fn input_queue(&mut self) -> &mut VecDeque<i64> {
+        &mut self.input
+    }
+
+    /// Get the queue of values the program has output, but which haven't been taken yet
+    pub fn output_queue(&mut self) -> &mut VecDeque<i64> {
+        &mut self.output
+    }
+
+    /// Start recording each instruction executed from now on; see [`trace`](Self::trace).
+    pub fn enable_trace(&mut self) {
+        self.trace.get_or_insert_with(Vec::new);
+    }
+
+    /// Get the instructions executed since tracing was enabled. If an instruction failed, it is
+    /// the last one in the trace.
+    pub fn trace(&self) -> &[TraceEntry] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    /// Stop the interpreter with an error once it has executed `limit` instructions in total,
+    /// e.g. to catch programs stuck in an infinite loop. `None` removes the limit.
+    pub fn set_step_limit(&mut self, limit: Option<usize>) {
+        self.step_limit = limit;
+    }
+
+    /// The number of instructions executed so far
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Read the value at an address, following the [Memory] semantics of the interpreter
+    pub fn get(&self, addr: usize) -> Result<i64, IntcodeError> {
+        self.read(addr)
+    }
+
+    /// Copy the contents of memory (including any growth past the initial tape)
+    pub fn memory(&self) -> Vec<i64> {
+        self.tape.to_vec()
+    }
+
+    /// Write a value to an address, following the [Memory] semantics of the interpreter
+    pub fn set(&mut self, addr: usize, value: i64) -> Result<(), IntcodeError> {
+        self.write(addr, value)
+    }
+
+    /// Save the whole state of the interpreter (memory, instruction pointer, I/O queues, etc.)
+    /// so it can be [restored](Self::restore) later, e.g. to try several inputs from the same
+    /// starting point.
+    ///
+    /// This is cheap: memory isn't copied up front, only a page at a time as it is written to.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot(self.clone())
+    }
+
+    /// Reset the interpreter to the state saved in a snapshot. The snapshot can be restored
+    /// again afterwards.
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.clone_from(&snapshot.0);
+    }
+
+    /// Run the interpreter until the program halts
+    pub fn run_to_halt(&mut self) -> Result<(), IntcodeError> {
+        while self.next()? != Opcode::Halt {}
 
         Ok(())
     }
 
+    /// Run the interpreter until it needs input which isn't available yet, outputs a value, or
+    /// halts.
+    ///
+    /// Values output this way are returned directly rather than being added to the output queue.
+    pub fn run_until_io(&mut self) -> Result<State, IntcodeError> {
+        loop {
+            // Pause before running an input instruction with nothing to read, so it can be re-run
+            // once there is.
+            let opcode = Opcode::try_from(self.read(self.ins_ptr)?);
+            if opcode == Ok(Opcode::Input) && self.input.is_empty() {
+                return Ok(State::NeedsInput);
+            }
+
+            match self.next()? {
+                Opcode::Output => {
+                    let value = self.output.pop_back().expect("an output was just written");
+                    return Ok(State::Output(value));
+                }
+                Opcode::Halt => return Ok(State::Halted),
+                _ => {}
+            }
+        }
+    }
+
     /// Run the next instruction on the tape
-    fn next(&mut self) -> Result<Opcode, Box<dyn std::error::Error>> {
-        let opcode = Opcode::try_from(self[self.ins_ptr])?;
-
-        match Opcode::try_from(self[self.ins_ptr])? {
-            Opcode::Add => self.add(),
-            Opcode::Mul => self.mul(),
-            Opcode::Halt => Ok(()),
-        }?;
-
-        Ok(opcode)
-    }
-
-    /// Perform the [`Add`](Opcode::Add) operation
-    fn add(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let l = self.ins_ptr + 1;
-        let l = *self
-            .tape
-            .get(l)
-            .ok_or_else(|| format!("Invalid addr: {}", l))?;
-        let l = self
-            .tape
-            .get(l as usize)
-            .ok_or_else(|| format!("Invalid addr: {l}"))?;
-
-        let r = self.ins_ptr + 2;
-        let r = *self
-            .tape
-            .get(r)
-            .ok_or_else(|| format!("Invalid addr: {}", r))?;
-        let r = self
-            .tape
-            .get(r as usize)
-            .ok_or_else(|| format!("Invalid addr: {r}"))?;
-
-        let res = l + r;
-
-        let ret = self.ins_ptr + 3;
-        let ret = *self
-            .tape
-            .get(ret)
-            .ok_or_else(|| format!("Invalid addr: {}", ret))?;
-
-        self[ret as usize] = res;
-        self.ins_ptr += 4; // Need to step past the arguments
+    fn next(&mut self) -> Result<Opcode, IntcodeError> {
+        if let Some(limit) = self.step_limit {
+            if self.steps >= limit {
+                return Err(IntcodeError::StepLimit {
+                    addr: self.ins_ptr,
+                    limit,
+                });
+            }
+        }
 
-        Ok(())
+        let ins = Instruction::decode(self.ins_ptr, |addr| self.read(addr))?;
+
+        if self.trace.is_some() {
+            let entry = self.trace_entry(&ins)?;
+            if let Some(trace) = &mut self.trace {
+                trace.push(entry);
+            }
+        }
+
+        let [a, b, c] = ins.params;
+        let mut next_addr = ins.next_addr();
+
+        match ins.opcode {
+            Opcode::Add => self.write(
+                self.addr(&ins, c)?,
+                checked(&ins, self.value(&ins, a)?.checked_add(self.value(&ins, b)?))?,
+            )?,
+            Opcode::Mul => self.write(
+                self.addr(&ins, c)?,
+                checked(&ins, self.value(&ins, a)?.checked_mul(self.value(&ins, b)?))?,
+            )?,
+            Opcode::Input => {
+                let value = self
+                    .input
+                    .pop_front()
+                    .ok_or(IntcodeError::NoInput { addr: ins.addr })?;
+                self.write(self.addr(&ins, a)?, value)?;
+            }
+            Opcode::Output => {
+                let value = self.value(&ins, a)?;
+                self.output.push_back(value);
+            }
+            Opcode::JumpIfTrue | Opcode::JumpIfFalse => {
+                let condition = ins.opcode == Opcode::JumpIfTrue;
+                if (self.value(&ins, a)? != 0) == condition {
+                    next_addr = to_addr(&ins, self.value(&ins, b)?)?;
+                }
+            }
+            Opcode::LessThan => {
+                let res = self.value(&ins, a)? < self.value(&ins, b)?;
+                self.write(self.addr(&ins, c)?, res as i64)?;
+            }
+            Opcode::Equals => {
+                let res = self.value(&ins, a)? == self.value(&ins, b)?;
+                self.write(self.addr(&ins, c)?, res as i64)?;
+            }
+            Opcode::AdjustRelativeBase => {
+                let offset = self.value(&ins, a)?;
+                self.relative_base = checked(&ins, self.relative_base.checked_add(offset))?;
+            }
+            Opcode::Halt => next_addr = ins.addr,
+        }
+
+        self.ins_ptr = next_addr;
+        self.steps += 1;
+
+        Ok(ins.opcode)
+    }
+
+    /// Record the instruction and the values of its operands; any writes are added to the entry
+    /// as the instruction runs.
+    fn trace_entry(&self, ins: &Instruction) -> Result<TraceEntry, IntcodeError> {
+        let params = ins.params();
+        let operands = match ins.opcode.writes() {
+            true => &params[..params.len() - 1],
+            false => params,
+        };
+
+        let instruction = disasm::render(ins);
+        let operands = operands
+            .iter()
+            .map(|&p| self.value(ins, p))
+            .collect::<Result<_, _>>()?;
+
+        Ok(TraceEntry {
+            addr: ins.addr,
+            instruction,
+            operands,
+            writes: Vec::new(),
+        })
+    }
+
+    /// Get the value of a parameter of the given instruction
+    fn value(&self, ins: &Instruction, param: Param) -> Result<i64, IntcodeError> {
+        match param.mode {
+            Mode::Immediate => Ok(param.raw),
+            Mode::Position | Mode::Relative => self.read(self.addr(ins, param)?),
+        }
+    }
+
+    /// Get the address a parameter of the given instruction points to
+    fn addr(&self, ins: &Instruction, param: Param) -> Result<usize, IntcodeError> {
+        match param.mode {
+            Mode::Position => to_addr(ins, param.raw),
+            Mode::Relative => to_addr(
+                ins,
+                checked(ins, self.relative_base.checked_add(param.raw))?,
+            ),
+            // Rejected when the instruction is decoded
+            Mode::Immediate => unreachable!("immediate parameters don't have an address"),
+        }
     }
 
-    /// Perform the [`Mul`](Opcode::Mul) operation
-    fn mul(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let l = self.ins_ptr + 1;
-        let l = *self
-            .tape
-            .get(l)
-            .ok_or_else(|| format!("Invalid addr: {}", l))?;
-        let l = self
-            .tape
-            .get(l as usize)
-            .ok_or_else(|| format!("Invalid addr: {l}"))?;
-
-        let r = self.ins_ptr + 2;
-        let r = *self
-            .tape
-            .get(r)
-            .ok_or_else(|| format!("Invalid addr: {}", r))?;
-        let r = self
-            .tape
-            .get(r as usize)
-            .ok_or_else(|| format!("Invalid addr: {r}"))?;
-
-        let res = l * r;
-
-        let ret = self.ins_ptr + 3;
-        let ret = *self
-            .tape
-            .get(ret)
-            .ok_or_else(|| format!("Invalid addr: {}", ret))?;
-
-        self[ret as usize] = res;
-        self.ins_ptr += 4; // Need to step past the arguments
+    /// Read a value from memory; see [Memory] for reads past the end of the tape
+    fn read(&self, addr: usize) -> Result<i64, IntcodeError> {
+        match (self.tape.get(addr), self.memory) {
+            (Some(&value), _) => Ok(value),
+            (None, Memory::Grow) => Ok(0),
+            (None, Memory::Fault) => Err(self.out_of_bounds(addr)),
+        }
+    }
+
+    /// Write a value to memory; see [Memory] for writes past the end of the tape
+    fn write(&mut self, addr: usize, value: i64) -> Result<(), IntcodeError> {
+        if addr >= self.tape.len() {
+            match self.memory {
+                Memory::Grow if addr < MAX_MEMORY => self.tape.grow(addr + 1),
+                Memory::Grow => return Err(self.memory_limit(addr)),
+                Memory::Fault => return Err(self.out_of_bounds(addr)),
+            }
+        }
+
+        if let Some(entry) = self.trace.as_mut().and_then(|t| t.last_mut()) {
+            entry.writes.push((addr, value));
+        }
+        *self.tape.get_mut(addr).expect("the tape was grown") = value;
 
         Ok(())
     }
+
+    fn out_of_bounds(&self, target: usize) -> IntcodeError {
+        IntcodeError::OutOfBounds {
+            addr: self.ins_ptr,
+            target,
+        }
+    }
+
+    fn memory_limit(&self, target: usize) -> IntcodeError {
+        IntcodeError::MemoryLimit {
+            addr: self.ins_ptr,
+            target,
+        }
+    }
 }
 
+/// The result of an instruction's checked arithmetic, or an error if it overflowed
+fn checked(ins: &Instruction, value: Option<i64>) -> Result<i64, IntcodeError> {
+    value.ok_or(IntcodeError::Overflow { addr: ins.addr })
+}
+
+/// Convert a value used by an instruction to an address, checking that it isn't negative
+fn to_addr(ins: &Instruction, value: i64) -> Result<usize, IntcodeError> {
+    usize::try_from(value).map_err(|_| IntcodeError::NegativeAddress {
+        addr: ins.addr,
+        value,
+    })
+}
+
+/// Memory past the end of the tape; see [Memory::Grow]
+const ZERO: i64 = 0;
+
 impl std::ops::Index<usize> for Interpreter {
-    type Output = u32;
+    type Output = i64;
 
+    /// Get the value at an address; unlike [`get`](Interpreter::get), this always treats memory
+    /// past the end of the tape as zeroes.
     fn index(&self, index: usize) -> &Self::Output {
-        self.tape.index(index)
+        self.tape.get(index).unwrap_or(&ZERO)
     }
 }
 
 impl std::ops::IndexMut<usize> for Interpreter {
+    /// Get a mutable reference to the value at an address, growing the tape if needed.
+    ///
+    /// # Panics
+    ///
+    /// With [`Memory::Fault`], panics if the address is past the end of the tape, and otherwise
+    /// if it's past [MAX_MEMORY]; use [`set`](Interpreter::set) to handle these as errors
+    /// instead.
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        self.tape.index_mut(index)
+        if index >= self.tape.len() {
+            if self.memory == Memory::Fault {
+                panic!("{}", self.out_of_bounds(index));
+            }
+            if index >= MAX_MEMORY {
+                panic!("{}", self.memory_limit(index));
+            }
+            self.tape.grow(index + 1);
+        }
+
+        self.tape.get_mut(index).expect("the tape was grown")
     }
 }
 
@@ -160,6 +662,16 @@ impl std::ops::IndexMut<usize> for Interpreter {
 mod tests {
     use super::*;
 
+    /// Run a program with the given inputs, returning its outputs
+    fn run(program: &str, input: &[i64]) -> Vec<i64> {
+        let mut program = Interpreter::parse(program).unwrap();
+        for &i in input {
+            program.push_input(i);
+        }
+        program.run_to_halt().unwrap();
+        program.drain_output()
+    }
+
     // Test cases described in day 02, these relate to the Add, Mul, and Halt Opcodes.
     #[test]
     fn d02() {
@@ -186,18 +698,394 @@ mod tests {
         for (input, expected) in cases {
             let start_tape = input
                 .split(",")
-                .map(|d| d.parse::<u32>().unwrap())
+                .map(|d| d.parse::<i64>().unwrap())
                 .collect::<Vec<_>>();
             let expected_end_tape = expected
                 .split(",")
-                .map(|d| d.parse::<u32>().unwrap())
+                .map(|d| d.parse::<i64>().unwrap())
                 .collect::<Vec<_>>();
 
             let mut program = Interpreter::new(start_tape);
             program.run_to_halt().unwrap();
-            let actual_end_tape = program.tape;
+            let actual_end_tape = program.tape.to_vec();
 
             assert_eq!(actual_end_tape, expected_end_tape, "Actual != Expected");
         }
     }
+
+    // Test cases described in day 05, these relate to the Input, Output, jump, and comparison
+    // Opcodes along with the Immediate parameter mode.
+    #[test]
+    fn d05() {
+        // Immediate mode & negative values
+        let mut program = Interpreter::parse("1002,4,3,4,33").unwrap();
+        program.run_to_halt().unwrap();
+        assert_eq!(program[4], 99);
+
+        let mut program = Interpreter::parse("1101,100,-1,4,0").unwrap();
+        program.run_to_halt().unwrap();
+        assert_eq!(program[4], 99);
+
+        // Output whatever is input
+        assert_eq!(run("3,0,4,0,99", &[1234]), vec![1234]);
+
+        let cases = vec![
+            // Is the input equal to 8? (Position mode)
+            ("3,9,8,9,10,9,4,9,99,-1,8", vec![(8, 1), (7, 0)]),
+            // Is the input less than 8? (Position mode)
+            ("3,9,7,9,10,9,4,9,99,-1,8", vec![(7, 1), (8, 0)]),
+            // Is the input equal to 8? (Immediate mode)
+            ("3,3,1108,-1,8,3,4,3,99", vec![(8, 1), (9, 0)]),
+            // Is the input less than 8? (Immediate mode)
+            ("3,3,1107,-1,8,3,4,3,99", vec![(-8, 1), (8, 0)]),
+            // Is the input non-zero? (Position mode jumps)
+            (
+                "3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9",
+                vec![(0, 0), (5, 1)],
+            ),
+            // Is the input non-zero? (Immediate mode jumps)
+            ("3,3,1105,-1,9,1101,0,0,12,4,12,99,1", vec![(0, 0), (-5, 1)]),
+            // Is the input below, equal to, or above 8?
+            (
+                "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99",
+                vec![(7, 999), (8, 1000), (9, 1001)],
+            ),
+        ];
+
+        for (program, io) in cases {
+            for (input, expected) in io {
+                assert_eq!(
+                    run(program, &[input]),
+                    vec![expected],
+                    "{program} <- {input}"
+                );
+            }
+        }
+    }
+
+    // Test cases described in day 09, these relate to the AdjustRelativeBase Opcode, the Relative
+    // parameter mode, large numbers, and memory beyond the initial program.
+    #[test]
+    fn d09() {
+        let quine = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
+        let expected = Interpreter::parse(quine).unwrap().tape.to_vec();
+        assert_eq!(run(quine, &[]), expected);
+
+        let output = run("1102,34915192,34915192,7,4,7,99,0", &[]);
+        assert_eq!(output[0].to_string().len(), 16);
+
+        assert_eq!(run("104,1125899906842624,99", &[]), vec![1125899906842624]);
+    }
+
+    /// Assemble a program, then run it with the given inputs, returning its outputs
+    fn run_asm(source: &str, input: &[i64]) -> Vec<i64> {
+        let tape = asm::assemble(source).unwrap();
+        let program = tape
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        run(&program, input)
+    }
+
+    // Each operation, with each of the parameter modes
+    #[test]
+    fn opcodes() {
+        let cases = vec![
+            // Arithmetic & comparisons, with position, immediate, and relative parameters
+            ("add [a] 2 -> [a]\nout [a]\nhlt\na: data 40", vec![42]),
+            ("mul [a] -3 -> [a]\nout [a]\nhlt\na: data 14", vec![-42]),
+            (
+                "arb a\nlt [rb+0] 10 -> [rb+1]\nout [rb+1]\nhlt\na: data 5 -1",
+                vec![1],
+            ),
+            (
+                "arb a\nlt 10 [rb+0] -> [rb+1]\nout [rb+1]\nhlt\na: data 5 -1",
+                vec![0],
+            ),
+            ("eq 7 7 -> [a]\nout [a]\nhlt\na: data -1", vec![1]),
+            ("eq 7 [a] -> [a]\nout [a]\nhlt\na: data -1", vec![0]),
+            // Input & output
+            ("in -> [a]\nout [a]\nout a\nhlt\na: data 0", vec![123, 7]),
+            (
+                "arb b\nin -> [rb-1]\nout [a]\nhlt\na: data 0\nb: data 0",
+                vec![123],
+            ),
+            // Jumps, to immediate, position, and relative targets
+            ("jt 1 end\nout 1\nend: hlt", vec![]),
+            ("jt 0 end\nout 1\nend: hlt", vec![1]),
+            (
+                "jf [zero] [target]\nout 1\nend: hlt\nzero: data 0\ntarget: data end",
+                vec![],
+            ),
+            (
+                "arb target\njf 1 [rb+0]\nout 1\nend: hlt\ntarget: data end",
+                vec![1],
+            ),
+            (
+                "arb target\njt 1 [rb+0]\nout 1\nend: hlt\ntarget: data end",
+                vec![],
+            ),
+            // Relative base adjustments accumulate and may be negative
+            (
+                "arb 10\narb [a]\narb -8\nout [rb+0]\nhlt\na: data 7",
+                vec![7],
+            ),
+        ];
+
+        for (source, expected) in cases {
+            let input = [123];
+            assert_eq!(run_asm(source, &input), expected, "{source}");
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let run_err = |program: &str| Interpreter::parse(program).unwrap().run_to_halt();
+
+        let cases = vec![
+            (
+                "1,0,0,0,42,99",
+                IntcodeError::UnknownOpcode { addr: 4, value: 42 },
+            ),
+            ("3,0,99", IntcodeError::NoInput { addr: 0 }),
+            (
+                "301,0,0,0,99",
+                IntcodeError::BadMode {
+                    addr: 0,
+                    param: 1,
+                    mode: 3,
+                },
+            ),
+            (
+                "11101,1,1,5,99,0",
+                IntcodeError::ImmediateWrite { addr: 0, param: 3 },
+            ),
+            (
+                "1,-1,0,0,99",
+                IntcodeError::NegativeAddress { addr: 0, value: -1 },
+            ),
+            (
+                "109,-5,22201,0,0,0,99",
+                IntcodeError::NegativeAddress { addr: 2, value: -5 },
+            ),
+            (
+                "1105,1,-1",
+                IntcodeError::NegativeAddress { addr: 0, value: -1 },
+            ),
+            (
+                "1101,9223372036854775807,1,0,99",
+                IntcodeError::Overflow { addr: 0 },
+            ),
+            (
+                "1102,-9223372036854775808,-1,0,99",
+                IntcodeError::Overflow { addr: 0 },
+            ),
+            (
+                "109,9223372036854775807,109,1,99",
+                IntcodeError::Overflow { addr: 2 },
+            ),
+            (
+                "109,9223372036854775807,204,1,99",
+                IntcodeError::Overflow { addr: 2 },
+            ),
+        ];
+
+        for (program, expected) in cases {
+            assert_eq!(run_err(program), Err(expected), "{program}");
+        }
+    }
+
+    #[test]
+    fn memory() {
+        // Write past the end of the tape, then read it back
+        let program = "1101,1,2,10,4,10,4,20,99";
+
+        let mut grow = Interpreter::parse(program).unwrap();
+        grow.run_to_halt().unwrap();
+        assert_eq!(grow.drain_output(), vec![3, 0]);
+        assert_eq!(grow.get(10), Ok(3));
+        assert_eq!(grow[100], 0);
+
+        let mut fault = Interpreter::parse(program).unwrap();
+        fault.set_memory(Memory::Fault);
+        assert_eq!(
+            fault.run_to_halt(),
+            Err(IntcodeError::OutOfBounds {
+                addr: 0,
+                target: 10
+            })
+        );
+        assert!(fault.get(9).is_err());
+        assert!(fault.set(9, 1).is_err());
+        assert!(fault.set(8, 1).is_ok());
+
+        // Growing memory has its limits
+        let mut huge = Interpreter::parse("1101,1,2,1000000000000,99").unwrap();
+        assert_eq!(
+            huge.run_to_halt(),
+            Err(IntcodeError::MemoryLimit {
+                addr: 0,
+                target: 1_000_000_000_000
+            })
+        );
+        assert!(huge.set(MAX_MEMORY - 1, 1).is_ok());
+        assert!(huge.set(MAX_MEMORY, 1).is_err());
+
+        // Running off the end of the tape
+        let mut fault = Interpreter::parse("1101,1,2,0").unwrap();
+        fault.set_memory(Memory::Fault);
+        assert_eq!(
+            fault.run_to_halt(),
+            Err(IntcodeError::OutOfBounds { addr: 4, target: 4 })
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn memory_fault_index() {
+        let mut program = Interpreter::parse("99").unwrap();
+        program.set_memory(Memory::Fault);
+        program[1] = 1;
+    }
+
+    #[test]
+    fn run_until_io() {
+        // Double each input until a zero is input
+        let mut program =
+            Interpreter::parse("3,20,1005,20,7,99,0,1002,20,2,20,4,20,1105,1,0").unwrap();
+
+        assert_eq!(program.run_until_io().unwrap(), State::NeedsInput);
+        assert_eq!(program.run_until_io().unwrap(), State::NeedsInput);
+
+        program.push_input(21);
+        assert_eq!(program.run_until_io().unwrap(), State::Output(42));
+        assert_eq!(program.run_until_io().unwrap(), State::NeedsInput);
+
+        program.push_input(-3);
+        program.push_input(0);
+        assert_eq!(program.run_until_io().unwrap(), State::Output(-6));
+        assert_eq!(program.run_until_io().unwrap(), State::Halted);
+        assert_eq!(program.run_until_io().unwrap(), State::Halted);
+        assert!(program.drain_output().is_empty());
+    }
+
+    #[test]
+    fn trace() {
+        let mut program = Interpreter::parse("1,9,10,3,2,3,11,0,99,30,40,50").unwrap();
+        program.enable_trace();
+        program.run_to_halt().unwrap();
+
+        let trace: Vec<_> = program.trace().iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            trace,
+            vec![
+                "    0: ADD [9] [10] -> [3]  (30, 40)  [3] <- 70",
+                "    4: MUL [3] [11] -> [0]  (70, 50)  [0] <- 3500",
+                "    8: HLT",
+            ]
+        );
+        assert_eq!(program.steps(), 3);
+
+        // The failing instruction is the last one traced
+        let mut program = Interpreter::parse("3,0,99").unwrap();
+        program.enable_trace();
+        assert!(program.run_to_halt().is_err());
+        assert_eq!(program.trace().last().unwrap().instruction, "IN -> [0]");
+    }
+
+    #[test]
+    fn step_limit() {
+        // Jump back to the start forever
+        let mut program = Interpreter::parse("1105,1,0").unwrap();
+        program.set_step_limit(Some(100));
+        assert_eq!(
+            program.run_to_halt(),
+            Err(IntcodeError::StepLimit {
+                addr: 0,
+                limit: 100
+            })
+        );
+        assert_eq!(program.steps(), 100);
+
+        let mut program = Interpreter::parse("1,0,0,0,99").unwrap();
+        program.set_step_limit(Some(2));
+        assert!(program.run_to_halt().is_ok());
+    }
+
+    #[test]
+    fn snapshot() {
+        let mut program =
+            Interpreter::parse("3,20,1005,20,7,99,0,1002,20,2,20,4,20,1105,1,0").unwrap();
+        program.push_input(21);
+        assert_eq!(program.run_until_io().unwrap(), State::Output(42));
+
+        let snapshot = program.snapshot();
+        for input in [1, 2, 3] {
+            program.restore(&snapshot);
+            program.push_input(input);
+            assert_eq!(program.run_until_io().unwrap(), State::Output(2 * input));
+            assert_eq!(program.get(20), Ok(2 * input));
+        }
+
+        // Restoring undoes writes, including any growth of memory
+        program.set(1000, 1).unwrap();
+        program.restore(&snapshot);
+        assert_eq!(program.get(20), Ok(42));
+        assert_eq!(program.get(1000), Ok(0));
+        assert_eq!(program.tape.len(), 21);
+    }
+
+    /// Compare restoring a snapshot with cloning the whole tape for each attempt of a day 2 style
+    /// search. Run with `cargo test -p aoc2019 --release -- --ignored --nocapture bench`.
+    #[test]
+    #[ignore = "benchmark"]
+    fn bench_snapshot() {
+        use std::time::Instant;
+
+        // `[0] = [noun] + [verb]`, followed by plenty of memory for the noun & verb to refer to
+        let mut tape = vec![1, 0, 0, 0, 99];
+        tape.extend(5..10_000);
+        let target = 99 + 98;
+
+        let start = Instant::now();
+        let mut clone_answer = None;
+        'clone: for noun in 0..=99 {
+            for verb in 0..=99 {
+                let mut tape = tape.clone();
+                tape[1] = noun;
+                tape[2] = verb;
+
+                let mut program = Interpreter::new(tape);
+                program.run_to_halt().unwrap();
+                if program[0] == target {
+                    clone_answer = Some(100 * noun + verb);
+                    break 'clone;
+                }
+            }
+        }
+        let clone_time = start.elapsed();
+
+        let start = Instant::now();
+        let mut snapshot_answer = None;
+        let mut program = Interpreter::new(tape.clone());
+        let snapshot = program.snapshot();
+        'snapshot: for noun in 0..=99 {
+            for verb in 0..=99 {
+                program.restore(&snapshot);
+                program.set(1, noun).unwrap();
+                program.set(2, verb).unwrap();
+
+                program.run_to_halt().unwrap();
+                if program[0] == target {
+                    snapshot_answer = Some(100 * noun + verb);
+                    break 'snapshot;
+                }
+            }
+        }
+        let snapshot_time = start.elapsed();
+
+        assert_eq!(clone_answer, snapshot_answer);
+        println!("clone per try: {clone_time:?}, snapshot & restore: {snapshot_time:?}");
+    }
 }
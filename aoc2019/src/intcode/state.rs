@@ -0,0 +1,114 @@
+//! Save an [Interpreter]'s state to (and load it from) a plain text format, so a program can be
+//! resumed later; e.g., by the `intcode-repl` binary.
+//!
+//! The format is one `key: value` pair per line, with lists of values comma-separated:
+//!
+//! ```text
+//! ins_ptr: 7
+//! relative_base: 0
+//! memory: grow
+//! steps: 12
+//! input: 1,2
+//! output:
+//! tape: 3,20,1005,20,7,99,0
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+
+use super::{Interpreter, Memory};
+
+impl Interpreter {
+    /// Save the state of the interpreter as text. Tracing and the step limit aren't saved.
+    pub fn save_state(&self) -> String {
+        let list = |values: &mut dyn Iterator<Item = i64>| {
+            values.map(|v| v.to_string()).collect::<Vec<_>>().join(",")
+        };
+        let memory = match self.memory {
+            Memory::Grow => "grow",
+            Memory::Fault => "fault",
+        };
+
+        format!(
+            "ins_ptr: {}\nrelative_base: {}\nmemory: {memory}\nsteps: {}\ninput: {}\noutput: {}\ntape: {}\n",
+            self.ins_ptr,
+            self.relative_base,
+            self.steps,
+            list(&mut self.input.iter().copied()),
+            list(&mut self.output.iter().copied()),
+            list(&mut self.tape.to_vec().into_iter()),
+        )
+    }
+
+    /// Load an interpreter from state previously [saved](Self::save_state).
+    pub fn load_state(state: &str) -> Result<Self, Box<dyn Error>> {
+        let fields: HashMap<&str, &str> = state
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| {
+                l.split_once(':')
+                    .map(|(k, v)| (k.trim(), v.trim()))
+                    .ok_or_else(|| format!("Invalid line in saved state: {l}"))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let field = |key: &str| {
+            fields
+                .get(key)
+                .copied()
+                .ok_or_else(|| format!("Saved state is missing `{key}`"))
+        };
+        let list = |key: &str| -> Result<VecDeque<i64>, Box<dyn Error>> {
+            let value = field(key)?;
+            if value.is_empty() {
+                return Ok(VecDeque::new());
+            }
+            Ok(value
+                .split(',')
+                .map(|v| v.trim().parse())
+                .collect::<Result<_, _>>()?)
+        };
+
+        let mut interpreter = Self::new(list("tape")?.into());
+        interpreter.ins_ptr = field("ins_ptr")?.parse()?;
+        interpreter.relative_base = field("relative_base")?.parse()?;
+        interpreter.steps = field("steps")?.parse()?;
+        interpreter.input = list("input")?;
+        interpreter.output = list("output")?;
+        interpreter.memory = match field("memory")? {
+            "grow" => Memory::Grow,
+            "fault" => Memory::Fault,
+            other => return Err(format!("Unknown memory mode: {other}").into()),
+        };
+
+        Ok(interpreter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::State;
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut program =
+            Interpreter::parse("3,20,1005,20,7,99,0,1002,20,2,20,4,20,1105,1,0").unwrap();
+        program.push_input(21);
+        program.push_input(4);
+        assert_eq!(program.run_until_io().unwrap(), State::Output(42));
+
+        let state = program.save_state();
+        assert_eq!(
+            state,
+            "ins_ptr: 13\nrelative_base: 0\nmemory: grow\nsteps: 4\ninput: 4\noutput: \ntape: 3,20,1005,20,7,99,0,1002,20,2,20,4,20,1105,1,0,0,0,0,0,42\n"
+        );
+
+        let mut loaded = Interpreter::load_state(&state).unwrap();
+        assert_eq!(loaded.save_state(), state);
+        assert_eq!(loaded.run_until_io().unwrap(), State::Output(8));
+
+        assert!(Interpreter::load_state("ins_ptr: 0").is_err());
+        assert!(Interpreter::load_state(&state.replace("grow", "shrink")).is_err());
+    }
+}
@@ -0,0 +1,123 @@
+//! Copy-on-write memory for the [Interpreter](super::Interpreter), so that cloning an interpreter
+//! (e.g., to [snapshot](super::Interpreter::snapshot) it) doesn't copy its whole tape.
+
+use std::rc::Rc;
+
+/// The number of values in each page of memory
+const PAGE_SIZE: usize = 256;
+
+/// A tape of values, split into fixed-size pages which are shared between clones until one of
+/// them writes to the page.
+#[derive(Debug, Clone, Default)]
+pub struct Tape {
+    pages: Vec<Rc<[i64; PAGE_SIZE]>>,
+
+    /// The number of values on the tape; the last page may be partly unused
+    len: usize,
+}
+
+impl Tape {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Get a reference to the value at an address, if it is on the tape
+    pub fn get(&self, addr: usize) -> Option<&i64> {
+        if addr >= self.len {
+            return None;
+        }
+
+        Some(&self.pages[addr / PAGE_SIZE][addr % PAGE_SIZE])
+    }
+
+    /// Get a mutable reference to the value at an address, if it is on the tape. If the page
+    /// holding the value is shared with another tape, it is copied first.
+    pub fn get_mut(&mut self, addr: usize) -> Option<&mut i64> {
+        if addr >= self.len {
+            return None;
+        }
+
+        let page = Rc::make_mut(&mut self.pages[addr / PAGE_SIZE]);
+        Some(&mut page[addr % PAGE_SIZE])
+    }
+
+    /// Grow the tape to hold `len` values, filling new values with zeroes. Does nothing if the
+    /// tape is already at least that long. New pages all share one page of zeroes until they're
+    /// written to.
+    pub fn grow(&mut self, len: usize) {
+        let num_pages = len.div_ceil(PAGE_SIZE);
+        if self.pages.len() < num_pages {
+            self.pages.resize(num_pages, Rc::new([0; PAGE_SIZE]));
+        }
+
+        self.len = self.len.max(len);
+    }
+
+    /// Copy the values on the tape into a `Vec`
+    pub fn to_vec(&self) -> Vec<i64> {
+        self.pages
+            .iter()
+            .flat_map(|page| page.iter())
+            .take(self.len)
+            .copied()
+            .collect()
+    }
+}
+
+impl From<Vec<i64>> for Tape {
+    fn from(values: Vec<i64>) -> Self {
+        let pages = values
+            .chunks(PAGE_SIZE)
+            .map(|chunk| {
+                let mut page = [0; PAGE_SIZE];
+                page[..chunk.len()].copy_from_slice(chunk);
+                Rc::new(page)
+            })
+            .collect();
+
+        Self {
+            pages,
+            len: values.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copy_on_write() {
+        let values: Vec<i64> = (0..600).collect();
+        let mut a = Tape::from(values.clone());
+        assert_eq!(a.len(), 600);
+        assert_eq!(a.to_vec(), values);
+
+        // Cloning shares every page
+        let b = a.clone();
+        assert!(a.pages.iter().zip(&b.pages).all(|(a, b)| Rc::ptr_eq(a, b)));
+
+        // Writing only copies the page written to
+        *a.get_mut(300).unwrap() = -1;
+        assert_eq!(a.get(300), Some(&-1));
+        assert_eq!(b.get(300), Some(&300));
+        assert!(Rc::ptr_eq(&a.pages[0], &b.pages[0]));
+        assert!(!Rc::ptr_eq(&a.pages[1], &b.pages[1]));
+        assert!(Rc::ptr_eq(&a.pages[2], &b.pages[2]));
+
+        assert_eq!(a.get(600), None);
+        assert_eq!(a.get_mut(600), None);
+    }
+
+    #[test]
+    fn grow() {
+        let mut tape = Tape::from(vec![1, 2, 3]);
+        tape.grow(1000);
+        assert_eq!(tape.len(), 1000);
+        assert_eq!(tape.get(999), Some(&0));
+        assert_eq!(tape.to_vec()[..4], [1, 2, 3, 0]);
+
+        tape.grow(10);
+        assert_eq!(tape.len(), 1000);
+    }
+}
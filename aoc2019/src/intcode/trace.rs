@@ -0,0 +1,39 @@
+//! Records of the instructions executed by an [Interpreter](super::Interpreter) with tracing
+//! enabled.
+
+use std::fmt;
+
+/// A single executed instruction
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceEntry {
+    /// The address of the instruction
+    pub addr: usize,
+
+    /// The disassembled instruction (see [`disasm`](super::disasm))
+    pub instruction: String,
+
+    /// The values of the parameters the instruction read (i.e., not including the parameter it
+    /// writes to, if any)
+    pub operands: Vec<i64>,
+
+    /// The `(address, value)` of each write the instruction made to memory
+    pub writes: Vec<(usize, i64)>,
+}
+
+impl fmt::Display for TraceEntry {
+    /// Format the entry like `    4: MUL [3] [11] -> [0]  (70, 50)  [0] <- 3500`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>5}: {}", self.addr, self.instruction)?;
+
+        if !self.operands.is_empty() {
+            let operands: Vec<_> = self.operands.iter().map(|o| o.to_string()).collect();
+            write!(f, "  ({})", operands.join(", "))?;
+        }
+
+        for (addr, value) in &self.writes {
+            write!(f, "  [{addr}] <- {value}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,4 @@
+//! Library half of the 2019 solutions, for code shared between the day runner and the other
+//! binaries (e.g., `intcode-repl`).
+
+pub mod intcode;
@@ -1,46 +1,20 @@
-use utils::PuzzleResult;
+use utils::{math, parse, PuzzleResult};
 
 pub fn part1(input: &str) -> PuzzleResult {
-    let input: Vec<_> = input
-        .lines()
-        .into_iter()
-        .map(|l| l.parse::<usize>().unwrap())
-        .collect();
-
-    for i in 0..input.len() {
-        for j in i..input.len() {
-            let i = input[i];
-            let j = input[j];
-
-            if i + j == 2020 {
-                return Ok(format!("{}", i * j));
-            }
-        }
-    }
-    Err("Answer not found".into())
+    product_of_sum(input, 2)
 }
 
 pub fn part2(input: &str) -> PuzzleResult {
-    let input: Vec<_> = input
-        .lines()
-        .into_iter()
-        .map(|l| l.parse::<usize>().unwrap())
-        .collect();
-
-    for i in 0..input.len() {
-        for j in i..input.len() {
-            for k in j..input.len() {
-                let i = input[i];
-                let j = input[j];
-                let k = input[k];
-
-                if i + j + k == 2020 {
-                    return Ok(format!("{}", i * j * k));
-                }
-            }
-        }
-    }
-    Err("Answer not found".into())
+    product_of_sum(input, 3)
+}
+
+/// Find `k` entries in the expense report which sum to 2020, and multiply them together
+fn product_of_sum(input: &str, k: usize) -> PuzzleResult {
+    let entries: Vec<i64> = parse::lines(input, |l| l.trim().parse::<i64>())?;
+
+    let indices = math::k_sum(&entries, k, 2020).ok_or("Answer not found")?;
+    let product: i64 = indices.iter().map(|&i| entries[i]).product();
+    Ok(format!("{product}"))
 }
 
 #[cfg(test)]
@@ -1,19 +1,8 @@
+use utils::parse::{self, Cursor, ParseError};
 use utils::PuzzleResult;
 
 pub fn part1(input: &str) -> PuzzleResult {
-    let instructions = input
-        .lines()
-        .map(|l| {
-            let mut it = l.split_whitespace();
-            let dir = it.next().ok_or_else(|| format!("No dir in line: '{l}'"))?;
-            let dist = it
-                .next()
-                .ok_or_else(|| format!("No dist in line: '{l}'"))?
-                .parse::<i32>()?;
-
-            Ok((dir, dist))
-        })
-        .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+    let instructions = parse_instructions(input)?;
 
     let mut h_dist = 0;
     let mut depth = 0;
@@ -31,19 +20,7 @@ pub fn part1(input: &str) -> PuzzleResult {
 }
 
 pub fn part2(input: &str) -> PuzzleResult {
-    let instructions = input
-        .lines()
-        .map(|l| {
-            let mut it = l.split_whitespace();
-            let dir = it.next().ok_or_else(|| format!("No dir in line: '{l}'"))?;
-            let dist = it
-                .next()
-                .ok_or_else(|| format!("No dist in line: '{l}'"))?
-                .parse::<i32>()?;
-
-            Ok((dir, dist))
-        })
-        .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+    let instructions = parse_instructions(input)?;
 
     let mut h_dist = 0;
     let mut depth = 0;
@@ -64,6 +41,18 @@ pub fn part2(input: &str) -> PuzzleResult {
     Ok(format!("{answer}"))
 }
 
+/// Parse each line into an instruction and its distance; e.g., `forward 5`
+fn parse_instructions(input: &str) -> Result<Vec<(&str, i32)>, ParseError> {
+    parse::lines(input, |l| -> Result<_, ParseError> {
+        let mut c = Cursor::new(l);
+        let dir = c.word()?;
+        let dist = c.int()?;
+        c.end()?;
+
+        Ok((dir, dist))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
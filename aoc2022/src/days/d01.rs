@@ -2,7 +2,6 @@ use utils::PuzzleResult;
 
 pub fn part1(input: &str) -> PuzzleResult {
     let max_calories: u32 = input
-        .replace("\r", "")
         .split("\n\n")
         .map(|elf| {
             elf.lines()
@@ -17,7 +16,6 @@ pub fn part1(input: &str) -> PuzzleResult {
 
 pub fn part2(input: &str) -> PuzzleResult {
     let mut elf_calories: Vec<u32> = input
-        .replace("\r", "")
         .split("\n\n")
         .map(|elf| {
             elf.lines()
@@ -1,4 +1,4 @@
-use std::error::Error;
+use macros::FromLine;
 use utils::PuzzleResult;
 
 pub fn part1(input: &str) -> PuzzleResult {
@@ -38,7 +38,6 @@ pub fn part2(input: &str) -> PuzzleResult {
 }
 
 fn parse_stacks(input: &str) -> Vec<Stack> {
-    let input = input.replace("\r", "");
     let input = input.split("\n\n").next().unwrap();
 
     // grab the ID of the last stack from the last line in the stacks section of the input
@@ -68,7 +67,6 @@ fn parse_stacks(input: &str) -> Vec<Stack> {
 }
 
 fn parse_instructions(input: &str) -> Vec<Instruction> {
-    let input = input.replace("\r", "");
     let input = input.split("\n\n").skip(1).next().unwrap();
 
     input
@@ -77,7 +75,8 @@ fn parse_instructions(input: &str) -> Vec<Instruction> {
         .collect()
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, FromLine)]
+#[format("move {cnt} from {src} to {dst}")]
 struct Instruction {
     /// The number of crates to move
     cnt: usize,
@@ -89,35 +88,6 @@ struct Instruction {
     dst: usize,
 }
 
-// marginally less lazy than some other days
-impl TryFrom<&str> for Instruction {
-    type Error = Box<dyn Error>;
-
-    fn try_from(s: &str) -> Result<Self, Self::Error> {
-        let mut parts = s.split(" ");
-
-        parts.next().ok_or(String::from("source string invalid"))?; // skip 'move'
-        let cnt = parts
-            .next()
-            .ok_or(String::from("source string invalid"))?
-            .parse()?;
-
-        parts.next().ok_or(String::from("source string invalid"))?; // skip 'from'
-        let src = parts
-            .next()
-            .ok_or(String::from("source string invalid"))?
-            .parse()?;
-
-        parts.next().ok_or(String::from("source string invalid"))?; // skip 'to'
-        let dst = parts
-            .next()
-            .ok_or(String::from("source string invalid"))?
-            .parse()?;
-
-        Ok(Self { cnt, src, dst })
-    }
-}
-
 #[derive(Debug, PartialEq)]
 struct Stack {
     id: u8,
@@ -1,5 +1,6 @@
 use std::error::Error;
 
+use macros::FromLine;
 use utils::PuzzleResult;
 
 pub fn part1(input: &str) -> PuzzleResult {
@@ -30,9 +31,12 @@ pub fn part2(input: &str) -> PuzzleResult {
 
 /// A single game of one or more [`Set`]s of red, green, & blue cubes
 /// pulled from a bag.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, FromLine)]
+#[format("Game {id}: {sets}")]
 struct Game {
     id: u32,
+    #[sep(";")]
+    #[try_from]
     sets: Vec<Set>,
 }
 
@@ -70,37 +74,6 @@ impl Game {
     }
 }
 
-impl TryFrom<&str> for Game {
-    type Error = Box<dyn Error>;
-
-    /// Sample input:
-    /// `Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green`
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let id = value
-            .split(":")
-            .next()
-            .ok_or::<Box<dyn Error>>(format!("No game component in: {value}").into())?
-            .trim()
-            .split(" ")
-            .skip(1)
-            .next()
-            .ok_or::<Box<dyn Error>>(format!("No game id in: {value}").into())?
-            .parse()?;
-
-        let sets: Result<Vec<Set>, _> = value
-            .split(":")
-            .skip(1)
-            .next()
-            .ok_or::<Box<dyn Error>>(format!("No sets in: {value}").into())?
-            .split(";")
-            .map(|s| Set::try_from(s))
-            .collect();
-        let sets = sets?;
-
-        Ok(Self { id, sets })
-    }
-}
-
 /// A single set of cubes pulled from a bag in a [`Game`]
 #[derive(Debug, PartialEq)]
 struct Set((u32, u32, u32));
@@ -2,17 +2,23 @@ use std::ops::Index;
 
 use itertools::Itertools;
 
+use utils::grid::{Grid, Point};
+use utils::render::{self, Rgb};
 use utils::PuzzleResult;
 
 pub fn part1(input: &str) -> PuzzleResult {
-    let schematic = Schematic::from(input);
+    let schematic = Schematic::try_from(input)?;
     let sum: u32 = schematic.part_numbers().iter().sum();
 
+    if render::enabled() {
+        render::save_png("2023-d03", &schematic.image(), |&c| c)?;
+    }
+
     Ok(format!("{sum}"))
 }
 
 pub fn part2(input: &str) -> PuzzleResult {
-    let schematic = Schematic::from(input);
+    let schematic = Schematic::try_from(input)?;
     let sum: u32 = schematic.gears().iter().map(|g| g.ratio()).sum();
 
     Ok(format!("{sum}"))
@@ -21,7 +27,7 @@ pub fn part2(input: &str) -> PuzzleResult {
 /// An engine schematic, consisting of a grid of numbers,
 /// symbols, and `.` characters.
 #[derive(Debug)]
-struct Schematic(Vec<Vec<char>>);
+struct Schematic(Grid<char>);
 
 impl Schematic {
     /// Fetch all the part numbers in the schematic.
@@ -33,7 +39,7 @@ impl Schematic {
             .iter()
             .map(|s| self.adjacent(s.coords()))
             .flatten()
-            .filter_map(|c| self.number_at(&c))
+            .filter_map(|c| self.number_at(c))
             .unique()
             .map(|sn| sn.val())
             .collect()
@@ -41,83 +47,50 @@ impl Schematic {
 
     /// Get all the [`Symbol`]s in the schematic
     fn symbols(&self) -> Vec<Symbol> {
-        let num_rows = self.0.len();
-        (&self.0)
-            .into_iter()
-            .flatten()
+        self.0
             .enumerate()
-            .filter_map(|(idx, &c)| {
+            .filter_map(|(p, &c)| {
                 if c.is_ascii_alphanumeric() || c == '.' {
                     None
                 } else {
-                    let row = idx / num_rows;
-                    let col = idx % num_rows;
-
-                    Some(Symbol::new(c, row, col))
+                    Some(Symbol::new(c, p.row, p.col))
                 }
             })
             .collect()
     }
 
-    /// Get all adjacent (including diagonals) [`Coordinates`]
-    /// in the schematic relative to the given `Coordinates`
-    fn adjacent(&self, loc: &Coordinates) -> Vec<Coordinates> {
-        let deltas = [
-            (0, 1),   // right
-            (1, 1),   // down right
-            (1, 0),   // down
-            (1, -1),  // down left
-            (0, -1),  // left
-            (-1, -1), // up left
-            (-1, 0),  // up
-            (-1, 1),  // up right
-        ];
-
-        deltas
-            .iter()
-            .filter_map(|(dr, dc)| {
-                let (r, c) = loc.0;
-                let row = r as i32 + dr;
-                let col = c as i32 + dc;
-
-                // Make sure the coordinate is in bounds
-                if row < 0 || col < 0 {
-                    None
-                } else if row >= self.0.len() as i32 || col >= self.0[0].len() as i32 {
-                    None
-                } else {
-                    Some(Coordinates::new(row as usize, col as usize))
-                }
-            })
-            .collect()
+    /// Get all adjacent (including diagonals) [`Point`]s
+    /// in the schematic relative to the given `Point`
+    fn adjacent(&self, loc: Point) -> Vec<Point> {
+        self.0.neighbors8(loc).collect()
     }
 
-    /// Get the number with a digit at the given [`Coordinates`].
+    /// Get the number with a digit at the given [`Point`].
     ///
     /// If no such number can be constructed, return [`None`]
-    fn number_at(&self, loc: &Coordinates) -> Option<SchemaNum> {
-        if !self[loc].is_numeric() {
+    fn number_at(&self, loc: Point) -> Option<SchemaNum> {
+        if !self.0[loc].is_numeric() {
             return None;
         }
 
         let digits_to_left = {
-            let (row, mut col) = loc.0;
+            let (row, mut col) = loc.into();
 
             while col > 0 && self[(row, col - 1)].is_numeric() {
                 col -= 1;
             }
 
-            &self.0[row][col..loc.col()]
+            &self.0.row(row)[col..loc.col]
         };
 
         let digits_to_right = {
-            let (row, mut col) = loc.0;
+            let (row, mut col) = loc.into();
 
-            while col + 1 < self.0[0].len() && self[(row, col + 1)].is_numeric() {
+            while col + 1 < self.0.cols() && self[(row, col + 1)].is_numeric() {
                 col += 1;
             }
 
-            &self.0[row][loc.col() + 1..=col]
+            &self.0.row(row)[loc.col + 1..=col]
         };
 
         let digits = {
@@ -127,7 +100,7 @@ impl Schematic {
                 digits.push(d.to_digit(10).unwrap());
             }
 
-            digits.push(self[loc].to_digit(10).unwrap());
+            digits.push(self.0[loc].to_digit(10).unwrap());
 
             for d in digits_to_right {
                 digits.push(d.to_digit(10).unwrap());
@@ -148,11 +121,34 @@ impl Schematic {
             num += d;
         }
 
-        let start = Coordinates::new(loc.row(), loc.col() - digits_to_left.len());
-        let stop = Coordinates::new(loc.row(), loc.col() + digits_to_right.len());
+        let start = Point::new(loc.row, loc.col - digits_to_left.len());
+        let stop = Point::new(loc.row, loc.col + digits_to_right.len());
         Some(SchemaNum::new(num, start, stop))
     }
 
+    /// Draw the schematic: symbols in yellow, part numbers in green, and other numbers in red
+    fn image(&self) -> Grid<Rgb> {
+        let mut image = self.0.map(|&c| match c {
+            '.' => render::BLACK,
+            c if c.is_ascii_digit() => [192, 0, 0],
+            _ => [255, 192, 0],
+        });
+
+        let symbols = self.symbols();
+        let part_numbers = symbols
+            .iter()
+            .flat_map(|s| self.adjacent(s.coords()))
+            .filter_map(|c| self.number_at(c))
+            .unique();
+        for num in part_numbers {
+            image
+                .region_mut(num.start, num.stop)
+                .for_each(|(_, px)| *px = [0, 192, 0]);
+        }
+
+        image
+    }
+
     /// Fetch all the gears in the schematic.
     ///
     /// A gear is a `*` [`Symbol`] adjacent to exactly two part numbers.
@@ -164,7 +160,7 @@ impl Schematic {
                     let adj_nums = self
                         .adjacent(s.coords())
                         .iter()
-                        .filter_map(|c| self.number_at(c))
+                        .filter_map(|&c| self.number_at(c))
                         .unique()
                         .collect_vec();
 
@@ -181,17 +177,11 @@ impl Schematic {
     }
 }
 
-impl From<&str> for Schematic {
-    fn from(s: &str) -> Self {
-        Self(s.lines().map(|l| l.trim().chars().collect()).collect())
-    }
-}
-
-impl Index<&Coordinates> for Schematic {
-    type Output = char;
+impl TryFrom<&str> for Schematic {
+    type Error = Box<dyn std::error::Error>;
 
-    fn index(&self, index: &Coordinates) -> &Self::Output {
-        &self.0[index.row()][index.col()]
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Ok(Self(s.parse()?))
     }
 }
 
@@ -199,65 +189,39 @@ impl Index<(usize, usize)> for Schematic {
     type Output = char;
 
     fn index(&self, index: (usize, usize)) -> &Self::Output {
-        let (row, col) = index;
-        &self.0[row][col]
+        &self.0[index]
     }
 }
 
 /// Helper type that represents a symbol in the [`Schematic`]
 /// as well as its coordinates in the `Schematic`
 #[derive(Debug, PartialEq, PartialOrd, Eq, Ord)]
-struct Symbol((char, Coordinates));
+struct Symbol((char, Point));
 
 impl Symbol {
     fn new(val: char, row: usize, col: usize) -> Self {
-        Self((val, Coordinates::new(row, col)))
+        Self((val, Point::new(row, col)))
     }
 
     fn val(&self) -> char {
         self.0 .0
     }
 
-    fn coords(&self) -> &Coordinates {
-        &self.0 .1
-    }
-}
-
-/// Helper type that represents a set of coordinates in the
-/// [`Schematic`]
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash)]
-struct Coordinates((usize, usize));
-
-impl Coordinates {
-    fn new(row: usize, col: usize) -> Self {
-        Self((row, col))
-    }
-
-    fn row(&self) -> usize {
-        self.0 .0
-    }
-
-    fn col(&self) -> usize {
+    fn coords(&self) -> Point {
         self.0 .1
     }
 }
 
-impl From<&(usize, usize)> for Coordinates {
-    fn from(tuple: &(usize, usize)) -> Self {
-        Self((tuple.0, tuple.1))
-    }
-}
-
 /// Helper type to represent a number in the [`Schematic`]
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Hash)]
 struct SchemaNum {
     val: u32,
-    start: Coordinates,
-    stop: Coordinates,
+    start: Point,
+    stop: Point,
 }
 
 impl SchemaNum {
-    fn new(val: u32, start: Coordinates, stop: Coordinates) -> Self {
+    fn new(val: u32, start: Point, stop: Point) -> Self {
         Self { val, start, stop }
     }
 
@@ -322,12 +286,21 @@ mod tests {
     mod schematic {
         use super::*;
 
+        #[test]
+        fn image() {
+            let image = Schematic::try_from(INPUT).unwrap().image();
+            assert_eq!(image[(0, 0)], [0, 192, 0], "467 is a part number");
+            assert_eq!(image[(0, 5)], [192, 0, 0], "114 is not a part number");
+            assert_eq!(image[(1, 3)], [255, 192, 0], "* is a symbol");
+            assert_eq!(image[(1, 0)], render::BLACK);
+        }
+
         #[test]
         fn part_numbers() {
             let mut expected = vec![467, 35, 633, 617, 592, 755, 664, 598];
             expected.sort();
 
-            let schematic = Schematic::from(INPUT);
+            let schematic = Schematic::try_from(INPUT).unwrap();
             let mut actual = schematic.part_numbers();
             actual.sort();
 
@@ -346,7 +319,7 @@ mod tests {
             ];
             expected.sort();
 
-            let mut actual = Schematic::from(INPUT).symbols();
+            let mut actual = Schematic::try_from(INPUT).unwrap().symbols();
             actual.sort();
 
             assert_eq!(actual, expected, "actual != expected!");
@@ -354,31 +327,31 @@ mod tests {
 
         #[test]
         fn adjacent() {
-            let schematic = Schematic::from(INPUT);
+            let schematic = Schematic::try_from(INPUT).unwrap();
             let cases = vec![
                 (
                     "top left corner",
-                    Coordinates::new(0, 0),
+                    Point::new(0, 0),
                     vec![(0, 1), (1, 0), (1, 1)],
                 ),
                 (
                     "top right corner",
-                    Coordinates::new(0, 9),
+                    Point::new(0, 9),
                     vec![(0, 8), (1, 8), (1, 9)],
                 ),
                 (
                     "bottom left corner",
-                    Coordinates::new(9, 0),
+                    Point::new(9, 0),
                     vec![(8, 0), (8, 1), (9, 1)],
                 ),
                 (
                     "bottom right corner",
-                    Coordinates::new(9, 9),
+                    Point::new(9, 9),
                     vec![(9, 8), (8, 8), (8, 9)],
                 ),
                 (
                     "somewhere in the middle",
-                    Coordinates::new(5, 4),
+                    Point::new(5, 4),
                     vec![
                         (5, 5),
                         (6, 5),
@@ -394,14 +367,13 @@ mod tests {
 
             for (comment, input, expected) in cases {
                 let expected = {
-                    let mut expected: Vec<Coordinates> =
-                        expected.iter().map(|tuple| tuple.into()).collect();
+                    let mut expected: Vec<Point> = expected.into_iter().map(Point::from).collect();
                     expected.sort();
                     expected
                 };
 
                 let actual = {
-                    let mut actual = schematic.adjacent(&input);
+                    let mut actual = schematic.adjacent(input);
                     actual.sort();
                     actual
                 };
@@ -412,23 +384,23 @@ mod tests {
 
         #[test]
         fn get_num() {
-            let schematic = Schematic::from(INPUT);
+            let schematic = Schematic::try_from(INPUT).unwrap();
             let cases = vec![
-                (Coordinates::new(0, 0), 467),
-                (Coordinates::new(2, 3), 35),
-                (Coordinates::new(6, 3), 592),
+                (Point::new(0, 0), 467),
+                (Point::new(2, 3), 35),
+                (Point::new(6, 3), 592),
             ];
 
             for (input, expected) in cases {
                 assert_eq!(
-                    schematic.number_at(&input).unwrap().val,
+                    schematic.number_at(input).unwrap().val,
                     expected,
                     "actual != expected"
                 );
             }
 
             assert_eq!(
-                schematic.number_at(&Coordinates::new(1, 0)),
+                schematic.number_at(Point::new(1, 0)),
                 None,
                 "actual != expected"
             );
@@ -436,15 +408,15 @@ mod tests {
 
         #[test]
         fn gears() {
-            let schematic = Schematic::from(INPUT);
+            let schematic = Schematic::try_from(INPUT).unwrap();
             let expected = vec![
                 Gear::new(
-                    SchemaNum::new(467, Coordinates::new(0, 0), Coordinates::new(0, 2)),
-                    SchemaNum::new(35, Coordinates::new(2, 2), Coordinates::new(2, 3)),
+                    SchemaNum::new(467, Point::new(0, 0), Point::new(0, 2)),
+                    SchemaNum::new(35, Point::new(2, 2), Point::new(2, 3)),
                 ),
                 Gear::new(
-                    SchemaNum::new(755, Coordinates::new(7, 6), Coordinates::new(7, 8)),
-                    SchemaNum::new(598, Coordinates::new(9, 5), Coordinates::new(9, 7)),
+                    SchemaNum::new(755, Point::new(7, 6), Point::new(7, 8)),
+                    SchemaNum::new(598, Point::new(9, 5), Point::new(9, 7)),
                 ),
             ];
 
@@ -1,6 +1,5 @@
+use macros::FromLine;
 use std::collections::VecDeque;
-use std::error::Error;
-
 use utils::PuzzleResult;
 
 pub fn part1(input: &str) -> PuzzleResult {
@@ -40,10 +39,13 @@ pub fn part2(input: &str) -> PuzzleResult {
     Ok(format!("{total_cards}"))
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, FromLine)]
+#[format("Card {id}: {winners} | {numbers}")]
 struct Card {
     id: usize,
+    #[sep(" ")]
     winners: Vec<u32>,
+    #[sep(" ")]
     numbers: Vec<u32>,
 }
 
@@ -94,56 +96,6 @@ impl Card {
     }
 }
 
-impl TryFrom<&str> for Card {
-    type Error = Box<dyn Error>;
-
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let id: usize = value
-            .split(":")
-            .next()
-            .ok_or_else(|| format!("No 'Card XX:' part in {value}"))?
-            .split_whitespace()
-            .filter(|s| s.len() > 0)
-            .skip(1)
-            .next()
-            .ok_or_else(|| format!("No number following 'Card ' in {value}"))?
-            .parse()?;
-
-        let winning_numbers: Result<Vec<u32>, _> = value
-            .split(":")
-            .skip(1)
-            .next()
-            .ok_or_else(|| format!("No numbers part in {value}"))?
-            .split("|")
-            .next()
-            .ok_or_else(|| format!("No '|' separator in {value}"))?
-            .split_whitespace()
-            .filter(|s| s.len() > 0)
-            .map(|s| s.trim().parse())
-            .collect();
-
-        let numbers: Result<Vec<u32>, _> = value
-            .split(":")
-            .skip(1)
-            .next()
-            .ok_or_else(|| format!("No numbers part in {value}"))?
-            .split("|")
-            .skip(1)
-            .next()
-            .ok_or_else(|| format!("No post-'|' part in {value}"))?
-            .split_whitespace()
-            .filter(|s| s.len() > 0)
-            .map(|s| s.parse())
-            .collect();
-
-        Ok(Self {
-            id,
-            numbers: numbers?,
-            winners: winning_numbers?,
-        })
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
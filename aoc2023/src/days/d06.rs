@@ -1,84 +1,42 @@
 use std::error::Error;
+use std::ops::RangeInclusive;
 
 use itertools::Itertools;
 
-use utils::PuzzleResult;
+use utils::{math, parse, PuzzleResult};
 
 pub fn part1(input: &str) -> PuzzleResult {
-    let prod: usize = parse_races(input)?
-        .iter()
-        .map(|r| r.winning_hold_times().count())
-        .product();
+    let prod: u64 = parse_races(input)?.iter().map(Race::ways_to_win).product();
     Ok(format!("{prod}"))
 }
 
 pub fn part2(input: &str) -> PuzzleResult {
-    let count: usize = parse_race(input)?.winning_hold_times().count();
+    let count = parse_race(input)?.ways_to_win();
     Ok(format!("{count}"))
 }
 
 fn parse_races(input: &str) -> Result<Vec<Race>, Box<dyn Error>> {
-    let times: Result<Vec<u64>, _> = input
-        .lines()
-        .next()
-        .ok_or_else(|| format!("No time line in: {input}"))?
-        .split(":")
-        .skip(1)
-        .next()
-        .ok_or_else(|| format!("No times after ':' in: {input}"))?
-        .split_whitespace()
-        .into_iter()
-        .map(|s| s.trim().parse())
-        .collect();
-
-    let distances: Result<Vec<u64>, _> = input
-        .lines()
-        .skip(1)
-        .next()
-        .ok_or_else(|| format!("No distance line in: {input}"))?
-        .split(":")
-        .skip(1)
-        .next()
-        .ok_or_else(|| format!("No distances after ':' in: {input}"))?
-        .split_whitespace()
-        .into_iter()
-        .map(|s| s.trim().parse())
-        .collect();
-
-    Ok(times?
-        .iter()
-        .zip(distances?.iter())
-        .map(|(&time, &dist)| Race::new(time, dist))
-        .collect())
+    match &parse::lines(input, parse::ints::<u64>)?[..] {
+        [times, distances] => Ok(times
+            .iter()
+            .zip(distances.iter())
+            .map(|(&time, &dist)| Race::new(time, dist))
+            .collect()),
+        _ => Err(format!("Expected time & distance lines in: {input}").into()),
+    }
 }
 
 fn parse_race(input: &str) -> Result<Race, Box<dyn Error>> {
-    let time: Result<u64, _> = input
-        .lines()
-        .next()
-        .ok_or_else(|| format!("No time line in: {input}"))?
-        .split(":")
-        .skip(1)
-        .next()
-        .ok_or_else(|| format!("No time after ':' in: {input}"))?
-        .split_whitespace()
-        .join("")
-        .parse();
-
-    let distance: Result<u64, _> = input
-        .lines()
-        .skip(1)
-        .next()
-        .ok_or_else(|| format!("No distance line in: {input}"))?
-        .split(":")
-        .skip(1)
-        .next()
-        .ok_or_else(|| format!("No distance after ':' in: {input}"))?
-        .split_whitespace()
-        .join("")
-        .parse();
-
-    Ok(Race::new(time?, distance?))
+    // The numbers on each line are really one number, with bad kerning
+    let lines = parse::lines(input, |l| {
+        parse::ints::<u64>(&l.split_whitespace().join(""))
+    })?;
+    match &lines[..] {
+        [time, distance] if time.len() == 1 && distance.len() == 1 => {
+            Ok(Race::new(time[0], distance[0]))
+        }
+        _ => Err(format!("Expected a time & distance in: {input}").into()),
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -99,10 +57,25 @@ impl Race {
 
     /// How long can you hold down the button for the boat to travel a distance greater
     /// than the record distance for this race?
-    fn winning_hold_times(&self) -> impl Iterator<Item = u64> + '_ {
-        (1..self.time())
-            .into_iter()
-            .filter(|hold_time| (hold_time * (self.time() - hold_time)) > self.dist())
+    ///
+    /// Holding the button for `h` ms travels `h * (time - h)` mm, so this is where
+    /// `h² - time*h + dist < 0`.
+    fn winning_hold_times(&self) -> RangeInclusive<u64> {
+        let (time, dist) = (self.time() as i64, self.dist() as i64);
+        match math::quadratic_below_zero(1, -time, dist) {
+            Some(holds) => *holds.start() as u64..=*holds.end() as u64,
+            None => RangeInclusive::new(1, 0),
+        }
+    }
+
+    /// How many ways are there to beat the record distance for this race?
+    fn ways_to_win(&self) -> u64 {
+        let holds = self.winning_hold_times();
+        if holds.is_empty() {
+            0
+        } else {
+            holds.end() - holds.start() + 1
+        }
     }
 }
 
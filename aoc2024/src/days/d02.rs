@@ -1,14 +1,7 @@
-use utils::PuzzleResult;
+use utils::{parse, PuzzleResult};
 
 pub fn part1(input: &str) -> PuzzleResult {
-    let reports = input
-        .lines()
-        .map(|l| {
-            l.split_whitespace()
-                .map(|level| level.parse::<u32>())
-                .collect::<Result<Vec<_>, _>>()
-        })
-        .collect::<Result<Vec<_>, _>>()?;
+    let reports = parse::lines(input, parse::ints::<u32>)?;
 
     let num_safe = reports.iter().filter(|r| report_is_safe(r)).count();
 
@@ -16,14 +9,7 @@ pub fn part1(input: &str) -> PuzzleResult {
 }
 
 pub fn part2(input: &str) -> PuzzleResult {
-    let reports = input
-        .lines()
-        .map(|l| {
-            l.split_whitespace()
-                .map(|level| level.parse::<u32>())
-                .collect::<Result<Vec<_>, _>>()
-        })
-        .collect::<Result<Vec<_>, _>>()?;
+    let reports = parse::lines(input, parse::ints::<u32>)?;
 
     let num_safe = reports
         .iter()
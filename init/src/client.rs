@@ -0,0 +1,222 @@
+//! Module with a small HTTP client for the Advent of Code site which tries to be a good citizen:
+//! requests are throttled (across invocations of `init`, not just within one), transient server
+//! errors are retried with exponential backoff, and "not yet available" responses are reported as
+//! errors instead of being mistaken for content.
+
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use std::thread::sleep;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The number of times to retry a request which failed with a transient error
+const MAX_RETRIES: u32 = 4;
+
+/// How long to wait before the first retry; this doubles with each subsequent retry
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+
+/// The body returned by Advent of Code when requesting an input that hasn't unlocked yet
+const NOT_UNLOCKED: &str = "Please don't repeatedly request this endpoint before it unlocks!";
+
+/// An authenticated, throttled client for the Advent of Code site
+pub struct Client {
+    http: reqwest::blocking::Client,
+
+    /// The minimum time to leave between the start of consecutive requests
+    min_interval: Duration,
+
+    /// File in which the time of the most recent request is recorded, so the throttle also
+    /// applies across separate invocations of `init`
+    state_path: PathBuf,
+}
+
+impl Client {
+    /// Create a new client that authenticates with the given session cookie and leaves at least
+    /// `min_interval` between requests. The time of the most recent request is kept in
+    /// `state_path`.
+    pub fn new(
+        session_cookie: &str,
+        min_interval: Duration,
+        state_path: PathBuf,
+    ) -> Result<Self, Box<dyn Error>> {
+        let cookie_jar = {
+            let cookie = format!("session={session_cookie}; Domain=adventofcode.com");
+            let url = "https://adventofcode.com/".parse::<reqwest::Url>()?;
+
+            let jar = reqwest::cookie::Jar::default();
+            jar.add_cookie_str(&cookie, &url);
+
+            jar
+        };
+
+        let user_agent = format!(
+            "Rust/{} (reqwest) GitHub/5donuts/Advent-of-Code Input Fetcher {}",
+            env!("RUSTC_VERSION"),
+            env!("CARGO_PKG_VERSION")
+        );
+
+        let http = reqwest::blocking::Client::builder()
+            .cookie_provider(std::sync::Arc::new(cookie_jar))
+            .user_agent(user_agent)
+            .build()?;
+
+        Ok(Self {
+            http,
+            min_interval,
+            state_path,
+        })
+    }
+
+    /// Request a page and return its body, retrying on transient failures.
+    pub fn get(&self, url: &str) -> Result<String, Box<dyn Error>> {
+        for attempt in 0..=MAX_RETRIES {
+            self.throttle();
+            let response = self.http.get(url).send();
+            self.record_request()?;
+
+            let reason = match response {
+                Ok(response) if response.status().is_server_error() => {
+                    format!("Error: {}", response.status().as_str())
+                }
+                Ok(response) => return Self::read(response),
+                Err(e) if e.is_timeout() || e.is_connect() => e.to_string(),
+                Err(e) => return Err(e.into()),
+            };
+
+            if attempt == MAX_RETRIES {
+                return Err(format!("{reason} (gave up after {MAX_RETRIES} retries)").into());
+            }
+
+            let backoff = backoff(attempt);
+            eprintln!("{reason}; retrying in {}s", backoff.as_secs());
+            sleep(backoff);
+        }
+
+        unreachable!("the final attempt always returns")
+    }
+
+    /// Check the response to a (non-server-error) request and return its body.
+    fn read(response: reqwest::blocking::Response) -> Result<String, Box<dyn Error>> {
+        let status = response.status();
+        let body = response.text()?;
+
+        if is_not_unlocked(&body) {
+            return Err(format!("Not unlocked yet: {}", body.trim()).into());
+        }
+
+        if !status.is_success() {
+            return Err(format!("Error: {}", status.as_str()).into());
+        }
+
+        // Note that the server may not send a `Content-Length` (e.g., for chunked responses), so
+        // just report the size of what we actually got.
+        println!("Got {} bytes", body.len());
+
+        Ok(body)
+    }
+
+    /// Sleep until at least [`min_interval`](Self::min_interval) has passed since the most
+    /// recent request. If the time of that request can't be read, there's nothing to wait for.
+    fn throttle(&self) {
+        let last = fs::read_to_string(&self.state_path)
+            .ok()
+            .and_then(|s| parse_last_request(&s));
+
+        if let Some(remaining) = throttle_delay(last, SystemTime::now(), self.min_interval) {
+            println!(
+                "Waiting {:.1}s before the next request",
+                remaining.as_secs_f32()
+            );
+            sleep(remaining);
+        }
+    }
+
+    /// Record the time of a request so the next one (possibly by a later `init` invocation) can be
+    /// throttled.
+    fn record_request(&self) -> Result<(), Box<dyn Error>> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        fs::write(&self.state_path, format!("{now}"))?;
+
+        Ok(())
+    }
+}
+
+/// How long to wait before retrying after the given (zero-based) failed attempt
+fn backoff(attempt: u32) -> Duration {
+    INITIAL_BACKOFF * 2u32.pow(attempt)
+}
+
+/// Whether a response body is the one sent for an input that hasn't unlocked yet
+fn is_not_unlocked(body: &str) -> bool {
+    body.starts_with(NOT_UNLOCKED)
+}
+
+/// Parse the time of the most recent request, as recorded by [Client::record_request]
+fn parse_last_request(contents: &str) -> Option<SystemTime> {
+    let millis = contents.trim().parse().ok()?;
+    UNIX_EPOCH.checked_add(Duration::from_millis(millis))
+}
+
+/// How long to wait at `now` so that at least `min_interval` passes after the request at `last`
+/// (if any), or `None` if there's no need to wait.
+fn throttle_delay(
+    last: Option<SystemTime>,
+    now: SystemTime,
+    min_interval: Duration,
+) -> Option<Duration> {
+    // If the clock went backwards, treat that the same as no time passing.
+    let elapsed = now.duration_since(last?).unwrap_or_default();
+    min_interval
+        .checked_sub(elapsed)
+        .filter(|remaining| !remaining.is_zero())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles() {
+        let delays: Vec<_> = (0..MAX_RETRIES).map(|a| backoff(a).as_secs()).collect();
+        assert_eq!(delays, vec![2, 4, 8, 16]);
+    }
+
+    #[test]
+    fn not_unlocked_body() {
+        assert!(is_not_unlocked(&format!("{NOT_UNLOCKED}\n")));
+        assert!(!is_not_unlocked("1\n2\n3\n"));
+        assert!(!is_not_unlocked(""));
+    }
+
+    #[test]
+    fn last_request() {
+        assert_eq!(
+            parse_last_request("1700000000123\n"),
+            Some(UNIX_EPOCH + Duration::from_millis(1_700_000_000_123))
+        );
+        assert_eq!(parse_last_request(""), None);
+        assert_eq!(parse_last_request("garbage"), None);
+        assert_eq!(parse_last_request("-5"), None);
+    }
+
+    #[test]
+    fn delay() {
+        let min = Duration::from_secs(5);
+        let last = UNIX_EPOCH + Duration::from_secs(1000);
+
+        assert_eq!(throttle_delay(None, last, min), None);
+        assert_eq!(throttle_delay(Some(last), last, min), Some(min));
+        assert_eq!(
+            throttle_delay(Some(last), last + Duration::from_secs(2), min),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(throttle_delay(Some(last), last + min, min), None);
+        assert_eq!(throttle_delay(Some(last), last + min * 2, min), None);
+
+        // The clock going backwards means waiting the full interval
+        assert_eq!(
+            throttle_delay(Some(last), last - Duration::from_secs(1), min),
+            Some(min)
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Module with the (very) small amount of calendar arithmetic needed to work with puzzle unlock
+//! times and other timestamps without pulling in a full date/time library.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Get the number of days since the Unix epoch of a date in the (proleptic) Gregorian calendar.
+///
+/// See: <https://howardhinnant.github.io/date_algorithms.html#days_from_civil>
+pub fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
+
+/// Get the `(year, month, day)` of a number of days since the Unix epoch; this is the inverse of
+/// [`days_from_civil`].
+///
+/// See: <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+pub fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+/// Format the (UTC) date of a point in time like `2024-12-01`.
+pub fn date_str(time: SystemTime) -> String {
+    let secs = match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => d.as_secs() as i64,
+        Err(e) => -(e.duration().as_secs() as i64),
+    };
+    let (year, month, day) = civil_from_days(secs.div_euclid(86400));
+
+    format!("{year}-{month:0>2}-{day:0>2}")
+}
+
+/// Format a duration like `2d 03:04:05`.
+pub fn duration_str(duration: Duration) -> String {
+    // Round up so a countdown never shows 00:00:00 before it has actually finished
+    let secs = duration.as_secs() + u64::from(duration.subsec_nanos() > 0);
+    let (days, secs) = (secs / 86400, secs % 86400);
+    let (hours, secs) = (secs / 3600, secs % 3600);
+    let (mins, secs) = (secs / 60, secs % 60);
+
+    if days > 0 {
+        format!("{days}d {hours:0>2}:{mins:0>2}:{secs:0>2}")
+    } else {
+        format!("{hours:0>2}:{mins:0>2}:{secs:0>2}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn civil_days() {
+        let cases = vec![
+            ((1970, 1, 1), 0),
+            ((2000, 3, 1), 11017),
+            ((2015, 12, 1), 16770),
+            ((2024, 12, 25), 20082),
+        ];
+        for (date, expected) in cases {
+            let (year, month, day) = date;
+            assert_eq!(days_from_civil(year, month, day), expected);
+            assert_eq!(civil_from_days(expected), date);
+        }
+    }
+
+    #[test]
+    fn format_date() {
+        let time = UNIX_EPOCH + Duration::from_secs(1733029200);
+        assert_eq!(date_str(time), "2024-12-01");
+    }
+
+    #[test]
+    fn format_duration() {
+        let cases = vec![
+            (Duration::from_millis(500), "00:00:01"),
+            (Duration::from_secs(3723), "01:02:03"),
+            (Duration::from_secs(2 * 86400 + 59), "2d 00:00:59"),
+        ];
+        for (duration, expected) in cases {
+            assert_eq!(duration_str(duration), expected);
+        }
+    }
+}
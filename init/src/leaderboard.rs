@@ -0,0 +1,282 @@
+//! Module to fetch, cache, and display private leaderboards.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use serde::Deserialize;
+
+use crate::client::Client;
+use crate::date::duration_str;
+use crate::unlock::unlock_time;
+
+/// Directory in which fetched leaderboards are cached
+const CACHE_DIR: &str = ".cache/leaderboard";
+
+/// How long to keep using a cached leaderboard; the Advent of Code site asks that private
+/// leaderboards be requested no more than once every 15 minutes.
+const CACHE_LIFETIME: Duration = Duration::from_secs(15 * 60);
+
+/// A private leaderboard, as returned by the Advent of Code API
+#[derive(Debug, Deserialize)]
+pub struct Leaderboard {
+    /// Members of the leaderboard, keyed by their user ID
+    members: HashMap<String, Member>,
+}
+
+/// A single member of a private [Leaderboard]
+#[derive(Debug, Deserialize)]
+struct Member {
+    /// The member's display name; this isn't set for anonymous users
+    name: Option<String>,
+    local_score: u64,
+    stars: u32,
+
+    /// The stars the member has earned, keyed by day then by part
+    #[serde(default)]
+    completion_day_level: HashMap<String, HashMap<String, Star>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Star {
+    get_star_ts: i64,
+}
+
+impl Leaderboard {
+    /// Fetch a leaderboard from the Advent of Code site, re-using a cached copy if it is recent
+    /// enough.
+    pub fn fetch(client: &Client, year: u16, id: u64) -> Result<Self, Box<dyn Error>> {
+        let cache_file = PathBuf::from(CACHE_DIR).join(format!("{year}-{id}.json"));
+
+        let age = fs::metadata(&cache_file)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok());
+        match age {
+            Some(age) if age < CACHE_LIFETIME => {
+                println!(
+                    "Using cached leaderboard from {} ago",
+                    duration_str(Duration::from_secs(age.as_secs()))
+                );
+                Self::parse(&fs::read_to_string(&cache_file)?)
+            }
+            _ => {
+                let url =
+                    format!("https://adventofcode.com/{year}/leaderboard/private/view/{id}.json");
+                let json = client.get(&url)?;
+
+                // Only cache what's actually a leaderboard, not (e.g.) an error page
+                let leaderboard = Self::parse(&json)?;
+                fs::create_dir_all(CACHE_DIR)?;
+                fs::write(&cache_file, &json)?;
+
+                Ok(leaderboard)
+            }
+        }
+    }
+
+    /// Read a leaderboard previously saved to the disk.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    fn parse(json: &str) -> Result<Self, Box<dyn Error>> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid leaderboard: {e}").into())
+    }
+
+    /// Get the members of the leaderboard along with their IDs, from highest ranked to lowest.
+    fn ranked(&self) -> Vec<(&str, &Member)> {
+        let mut members: Vec<_> = self
+            .members
+            .iter()
+            .map(|(id, member)| (id.as_str(), member))
+            .collect();
+
+        // Ties are broken in favour of whoever got their stars first
+        members.sort_by(|(a_id, a), (b_id, b)| {
+            b.local_score
+                .cmp(&a.local_score)
+                .then(b.stars.cmp(&a.stars))
+                .then(a.last_star_ts().cmp(&b.last_star_ts()))
+                .then(a_id.cmp(b_id))
+        });
+
+        members
+    }
+
+    /// Render the overall rankings, along with the stars each member has for each day.
+    pub fn summary(&self) -> String {
+        let members = self.ranked();
+        let mut out = format!("{} members\n\n", members.len());
+
+        // Label each day's column vertically; e.g., day 12 is a `1` above a `2`.
+        let tens: String = (1..=25)
+            .map(|day| if day < 10 { ' ' } else { tens_digit(day) })
+            .collect();
+        let ones: String = (1..=25).map(ones_digit).collect();
+        out.push_str(&format!("{:11}{}\n", "", tens.trim_end()));
+        out.push_str(&format!("{:11}{ones}\n", ""));
+
+        let mut rank = 0;
+        let mut prev_score = None;
+        for (i, (id, member)) in members.iter().enumerate() {
+            // Members with the same score share a rank
+            if prev_score != Some(member.local_score) {
+                rank = i + 1;
+                prev_score = Some(member.local_score);
+            }
+
+            let stars: String = (1..=25)
+                .map(|day| match member.stars_on(day) {
+                    2 => '*',
+                    1 => '+',
+                    _ => '.',
+                })
+                .collect();
+
+            out.push_str(&format!(
+                "{rank:>3}) {:>5} {stars} {}\n",
+                member.local_score,
+                member.display_name(id)
+            ));
+        }
+
+        out
+    }
+
+    /// Render when each member earned each star for a single day (relative to when the puzzle
+    /// unlocked), and how long they took to go from part 1 to part 2.
+    pub fn day(&self, year: u16, day: u8) -> String {
+        let unlock = unlock_time(year, day);
+        let since_unlock = |ts: i64| {
+            let time = UNIX_EPOCH + Duration::from_secs(ts as u64);
+            time.duration_since(unlock).unwrap_or_default()
+        };
+
+        let mut members: Vec<_> = self
+            .members
+            .iter()
+            .filter_map(|(id, member)| {
+                let p1 = member.star_ts(day, 1)?;
+                let p2 = member.star_ts(day, 2);
+                Some((member.display_name(id), p1, p2))
+            })
+            .collect();
+
+        // Whoever finished the whole puzzle first ranks highest; members with just the first
+        // star rank below everyone who finished.
+        members.sort_by_key(|(name, p1, p2)| (p2.is_none(), *p2, *p1, name.clone()));
+
+        let name_width = members
+            .iter()
+            .map(|(name, _, _)| name.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut out = format!("Day {day}\n\n");
+        out.push_str(&format!(
+            "{:5}{:name_width$}  {:>11}  {:>11}  {:>11}\n",
+            "", "", "Part 1", "Part 2", "Delta"
+        ));
+
+        for (i, (name, p1, p2)) in members.iter().enumerate() {
+            let p1_time = duration_str(since_unlock(*p1));
+            let (p2_time, delta) = match p2 {
+                Some(p2) => (
+                    duration_str(since_unlock(*p2)),
+                    duration_str(Duration::from_secs((p2 - p1).max(0) as u64)),
+                ),
+                None => ("-".to_string(), "-".to_string()),
+            };
+
+            out.push_str(&format!(
+                "{:>3}) {name:name_width$}  {p1_time:>11}  {p2_time:>11}  {delta:>11}\n",
+                i + 1
+            ));
+        }
+
+        out
+    }
+}
+
+impl Member {
+    /// Get the time at which the member earned the star for the given part of the given day.
+    fn star_ts(&self, day: u8, part: u8) -> Option<i64> {
+        self.completion_day_level
+            .get(&day.to_string())
+            .and_then(|parts| parts.get(&part.to_string()))
+            .map(|star| star.get_star_ts)
+    }
+
+    /// Get the number of stars the member earned on the given day.
+    fn stars_on(&self, day: u8) -> usize {
+        self.completion_day_level
+            .get(&day.to_string())
+            .map(|parts| parts.len())
+            .unwrap_or(0)
+    }
+
+    /// Get the time at which the member earned their most recent star.
+    fn last_star_ts(&self) -> i64 {
+        self.completion_day_level
+            .values()
+            .flat_map(|parts| parts.values())
+            .map(|star| star.get_star_ts)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn display_name(&self, id: &str) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{id})"),
+        }
+    }
+}
+
+fn tens_digit(day: u8) -> char {
+    char::from(b'0' + day / 10)
+}
+
+fn ones_digit(day: u8) -> char {
+    char::from(b'0' + day % 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../fixtures/leaderboard.json");
+
+    #[test]
+    fn summary() {
+        let leaderboard = Leaderboard::parse(FIXTURE).unwrap();
+        let expected = r#"4 members
+
+                    1111111111222222
+           1234567890123456789012345
+  1)    14 **....................... alice
+  2)    11 *+....................... bob
+  3)     2 +........................ (anonymous user #1003)
+  4)     0 ......................... dave
+"#;
+
+        assert_eq!(leaderboard.summary(), expected);
+    }
+
+    #[test]
+    fn day() {
+        let leaderboard = Leaderboard::parse(FIXTURE).unwrap();
+        let expected = r#"Day 1
+
+                                  Part 1       Part 2        Delta
+  1) alice                      00:05:00     00:10:00     00:05:00
+  2) bob                        00:03:20     00:13:20     00:10:00
+  3) (anonymous user #1003)  1d 23:26:40            -            -
+"#;
+
+        assert_eq!(leaderboard.day(2024, 1), expected);
+    }
+}
@@ -5,10 +5,21 @@ use std::error::Error;
 use std::fs;
 use std::io::{stdin, stdout, Write};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use clap::{Parser, Subcommand};
 
+use client::Client;
+use leaderboard::Leaderboard;
+
+mod client;
+mod date;
+mod leaderboard;
+mod puzzle;
+mod session;
+mod status;
 mod templates;
+mod unlock;
 
 #[derive(Debug, Subcommand)]
 enum Command {
@@ -16,29 +27,118 @@ enum Command {
     /// within that year.
     New {
         /// The puzzle year to initialize.
+        #[clap(value_parser = year_parser())]
         year: u16,
 
         /// The day to initialize within the specified year; if omitted, only initialize the
         /// workspace crate.
+        #[clap(value_parser = day_parser())]
         day: Option<u8>,
     },
 
     /// Fetch your input to a particular puzzle.
     Fetch {
         /// The year of the puzzle for which to fetch the input.
+        #[clap(value_parser = year_parser())]
         year: u16,
 
         /// The day of the puzzle for which to fetch the input; if omitted, fetch & cache all
         /// inputs required for the defined day runners (i.e., any present `src/days/dXX.rs`
         /// files).
+        #[clap(value_parser = day_parser())]
         day: Option<u8>,
 
+        /// Path to the file containing your session cookie value; ignored if the `AOC_SESSION`
+        /// environment variable is set.
+        #[clap(short, long, value_parser, default_value = ".session-cookie")]
+        session_cookie_path: PathBuf,
+
+        /// Also fetch the puzzle description, saving it as Markdown alongside the input,
+        /// extracting any example blocks it contains into `<day>.example.<n>` files, and
+        /// recording the answers to any solved parts in `<day>.answers`.
+        #[clap(short, long)]
+        description: bool,
+
+        /// The minimum number of seconds to wait between requests to the Advent of Code site;
+        /// this also applies across separate invocations.
+        #[clap(short, long, default_value_t = MIN_INTERVAL_SECS)]
+        min_interval: u64,
+
+        /// If the puzzle hasn't unlocked yet, count down until it does instead of exiting with an
+        /// error.
+        #[clap(short, long)]
+        wait: bool,
+    },
+
+    /// Manage the session cookie used to fetch puzzle inputs & descriptions.
+    Auth {
+        #[command(subcommand)]
+        action: AuthAction,
+
         /// Path to the file containing your session cookie value.
         #[clap(short, long, value_parser, default_value = ".session-cookie")]
         session_cookie_path: PathBuf,
     },
+
+    /// Show the standings on a private leaderboard.
+    Leaderboard {
+        /// The year of the leaderboard to show.
+        #[clap(value_parser = year_parser())]
+        year: u16,
+
+        /// The ID of the private leaderboard (i.e., the number at the end of its URL).
+        id: u64,
+
+        /// Show when each member earned their stars on this day instead of the overall rankings.
+        #[clap(short, long, value_parser = day_parser())]
+        day: Option<u8>,
+
+        /// Read the leaderboard JSON from this file instead of fetching it.
+        #[clap(short, long, value_parser)]
+        file: Option<PathBuf>,
+
+        /// Path to the file containing your session cookie value; ignored if the `AOC_SESSION`
+        /// environment variable is set.
+        #[clap(short, long, value_parser, default_value = ".session-cookie")]
+        session_cookie_path: PathBuf,
+    },
+
+    /// Show which puzzles have been solved across all years, along with any missing inputs.
+    Status,
+}
+
+#[derive(Debug, Subcommand)]
+enum AuthAction {
+    /// Save a session cookie, readable only by you.
+    Set {
+        /// The value of the `session` cookie for adventofcode.com; if omitted, it is read from
+        /// stdin (which keeps it out of your shell history).
+        cookie: Option<String>,
+    },
+
+    /// Check that the session cookie is accepted by the Advent of Code site.
+    Validate,
+
+    /// Show roughly when the stored session cookie expires.
+    Expiry,
+}
+
+/// The default minimum number of seconds to leave between requests to the Advent of Code site
+const MIN_INTERVAL_SECS: u64 = 3;
+
+/// Parser for puzzle years; there were no puzzles before the first year Advent of Code ran.
+fn year_parser() -> clap::builder::RangedI64ValueParser<u16> {
+    clap::value_parser!(u16).range(unlock::FIRST_YEAR as i64..)
 }
 
+/// Parser for puzzle days; there are 25 puzzles each year.
+fn day_parser() -> clap::builder::RangedI64ValueParser<u8> {
+    clap::value_parser!(u8).range(1..=25)
+}
+
+/// File in which the time of the most recent request to the Advent of Code site is recorded
+const THROTTLE_STATE_PATH: &str = ".last-request";
+
 #[derive(Debug, Parser)]
 #[clap(author, version, about)]
 struct Args {
@@ -55,7 +155,33 @@ fn main() -> Result<(), Box<dyn Error>> {
             year,
             day,
             session_cookie_path,
-        } => fetch(year, day, &session_cookie_path),
+            description,
+            min_interval,
+            wait,
+        } => fetch(
+            year,
+            day,
+            &session_cookie_path,
+            description,
+            Duration::from_secs(min_interval),
+            wait,
+        ),
+        Command::Auth {
+            action,
+            session_cookie_path,
+        } => auth(action, &session_cookie_path),
+        Command::Leaderboard {
+            year,
+            id,
+            day,
+            file,
+            session_cookie_path,
+        } => leaderboard(year, id, day, file.as_deref(), &session_cookie_path),
+        Command::Status => {
+            let cwd = std::env::current_dir()?;
+            print!("{}", status::status(&cwd)?);
+            Ok(())
+        }
     }?;
 
     Ok(())
@@ -123,24 +249,21 @@ fn init(year: u16, day: Option<u8>) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-fn fetch(year: u16, day: Option<u8>, session_cookie_path: &Path) -> Result<(), Box<dyn Error>> {
+fn fetch(
+    year: u16,
+    day: Option<u8>,
+    session_cookie_path: &Path,
+    description: bool,
+    min_interval: Duration,
+    wait: bool,
+) -> Result<(), Box<dyn Error>> {
     let cwd = std::env::current_dir()?;
     let crate_name = format!("aoc{year}");
     let ws_crate_root = Path::new(&cwd).join(&crate_name);
     let input_dir = ws_crate_root.join("input");
 
-    // Ensure the path to the session cookie file exists, then load the cookie.
-    if !(session_cookie_path.exists() && session_cookie_path.is_file()) {
-        return Err(format!(
-            "No such session cookie file: {}",
-            session_cookie_path
-                .to_str()
-                .unwrap_or("err: path contains non-unicode data")
-        )
-        .into());
-    }
-
-    let session_cookie = fs::read_to_string(session_cookie_path)?;
+    let session_cookie = session::load(session_cookie_path)?;
+    let client = Client::new(&session_cookie, min_interval, THROTTLE_STATE_PATH.into())?;
 
     // Check if the workspace crate exists; if not, exit with an error
     if !(ws_crate_root.exists() && ws_crate_root.is_dir()) {
@@ -156,12 +279,18 @@ fn fetch(year: u16, day: Option<u8>, session_cookie_path: &Path) -> Result<(), B
     // If a day was specified, check to see if the input file for that day already exists.
     // If not, attempt to download it using the specified session cookie.
     if let Some(day) = day {
+        unlock::ensure_unlocked(year, day, wait)?;
+
         let day_input_file = input_dir.join(format!("{day}"));
 
         if day_input_file.exists() && day_input_file.is_file() {
             println!("Input file {crate_name}/input/{day} already exists; skipping download.");
         } else {
-            fetch_day_input(year, day, &session_cookie, &day_input_file)?;
+            fetch_day_input(year, day, &client, &day_input_file)?;
+        }
+
+        if description {
+            fetch_day_description(year, day, &client, &input_dir)?;
         }
     }
     // If no day was specified, check the list of present day runners (i.e.,
@@ -195,16 +324,18 @@ fn fetch(year: u16, day: Option<u8>, session_cookie_path: &Path) -> Result<(), B
         println!("Found {} day runners for year {year}", day_runners.len());
 
         for day in day_runners {
+            unlock::ensure_unlocked(year, day, wait)?;
+
             let day_input_file = input_dir.join(format!("{day}"));
             if day_input_file.exists() && day_input_file.is_file() {
                 println!("Input file {crate_name}/input/{day} already exists; skipping download.");
-                continue;
+            } else {
+                fetch_day_input(year, day, &client, &day_input_file)?;
             }
 
-            fetch_day_input(year, day, &session_cookie, &day_input_file)?;
-
-            // Give a pause to at least try to be nice to the AoC servers
-            std::thread::sleep(std::time::Duration::from_secs(3));
+            if description {
+                fetch_day_description(year, day, &client, &input_dir)?;
+            }
         }
 
         println!("done!");
@@ -213,11 +344,85 @@ fn fetch(year: u16, day: Option<u8>, session_cookie_path: &Path) -> Result<(), B
     Ok(())
 }
 
+fn auth(action: AuthAction, session_cookie_path: &Path) -> Result<(), Box<dyn Error>> {
+    match action {
+        AuthAction::Set { cookie } => {
+            let cookie = match cookie {
+                Some(cookie) => cookie,
+                None => {
+                    print!("Session cookie: ");
+                    let _ = stdout().flush();
+
+                    let mut cookie = String::new();
+                    stdin().read_line(&mut cookie)?;
+                    cookie
+                }
+            };
+
+            session::store(session_cookie_path, &cookie)?;
+            println!("Wrote {}", session_cookie_path.display());
+        }
+        AuthAction::Validate => {
+            let session_cookie = session::load(session_cookie_path)?;
+            let client = Client::new(
+                &session_cookie,
+                Duration::from_secs(MIN_INTERVAL_SECS),
+                THROTTLE_STATE_PATH.into(),
+            )?;
+
+            let user = session::validate(&client)?;
+            println!("Session is valid; logged in as {user}");
+        }
+        AuthAction::Expiry => {
+            if std::env::var_os(session::ENV_VAR).is_some() {
+                println!(
+                    "Note: {} is set, so the session cookie file isn't being used",
+                    session::ENV_VAR
+                );
+            }
+
+            println!("{}", session::expiry_str(session_cookie_path)?);
+        }
+    }
+
+    Ok(())
+}
+
+fn leaderboard(
+    year: u16,
+    id: u64,
+    day: Option<u8>,
+    file: Option<&Path>,
+    session_cookie_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let leaderboard = match file {
+        Some(file) => Leaderboard::load(file)?,
+        None => {
+            let session_cookie = session::load(session_cookie_path)?;
+            let client = Client::new(
+                &session_cookie,
+                Duration::from_secs(MIN_INTERVAL_SECS),
+                THROTTLE_STATE_PATH.into(),
+            )?;
+
+            Leaderboard::fetch(&client, year, id)?
+        }
+    };
+
+    println!("Private leaderboard {id} ({year})\n");
+    match day {
+        Some(day) => print!("{}", leaderboard.day(year, day)),
+        None => print!("{}", leaderboard.summary()),
+    }
+
+    Ok(())
+}
+
 /// Download the input file for a single day and write it to the disk
 fn fetch_day_input(
     year: u16,
     day: u8,
-    session_cookie: &str,
+    client: &Client,
     output_path: &Path,
 ) -> Result<(), Box<dyn Error>> {
     let download_url = format!("https://adventofcode.com/{year}/day/{day}/input");
@@ -226,40 +431,82 @@ fn fetch_day_input(
         return Ok(());
     }
 
-    let cookie_jar = {
-        let cookie = format!("session={session_cookie}; Domain=adventofcode.com");
-        let url = "https://adventofcode.com/".parse::<reqwest::Url>()?;
+    let input_text = client.get(&download_url)?;
+    utils::input::validate(&input_text)?;
 
-        let jar = reqwest::cookie::Jar::default();
-        jar.add_cookie_str(&cookie, &url);
+    let input_text = utils::input::normalize(&input_text);
+    fs::write(output_path, &input_text)?;
+    utils::input::record_checksum(output_path, &input_text)?;
 
-        jar
-    };
-
-    let user_agent = format!(
-        "Rust/{} (reqwest) GitHub/5donuts/Advent-of-Code Input Fetcher {}",
-        env!("RUSTC_VERSION"),
-        env!("CARGO_PKG_VERSION")
-    );
+    println!("Wrote {}", output_path.to_str().unwrap());
 
-    let request = reqwest::blocking::Client::builder()
-        .cookie_provider(std::sync::Arc::new(cookie_jar))
-        .build()?
-        .get(download_url)
-        .header(reqwest::header::USER_AGENT, user_agent);
+    Ok(())
+}
 
-    let response = request.send()?;
+/// Download the puzzle description for a single day, then write it to the disk as Markdown
+/// (`<day>.md`) along with any example blocks it contains (`<day>.example.<n>`) and the answers
+/// to any parts that have been solved (`<day>.answers`).
+///
+/// Once part 2 of a puzzle is unlocked, its description is on the same page as part 1; so, a
+/// description is only re-downloaded if part 2 or either answer isn't already present.
+fn fetch_day_description(
+    year: u16,
+    day: u8,
+    client: &Client,
+    input_dir: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let crate_name = format!("aoc{year}");
+    let description_file = input_dir.join(format!("{day}.md"));
+    let answers_file = input_dir.join(format!("{day}.answers"));
+
+    if description_file.exists() && description_file.is_file() {
+        let existing = fs::read_to_string(&description_file)?;
+        let answers = fs::read_to_string(&answers_file).unwrap_or_default();
+
+        // Day 25 only has a single answer to record
+        let expected_answers = if day == 25 { 1 } else { 2 };
+        if existing.contains("--- Part Two ---") && answers.lines().count() >= expected_answers {
+            println!(
+                "Description {crate_name}/input/{day}.md is already complete; skipping download."
+            );
+            return Ok(());
+        }
+    }
 
-    if !response.status().is_success() {
-        return Err(format!("Error: {}", response.status().as_str()).into());
+    let download_url = format!("https://adventofcode.com/{year}/day/{day}");
+    if !prompt(&format!("Fetching '{download_url}'. Proceed y/N? ")) {
+        println!("Ok; exiting");
+        return Ok(());
     }
 
-    println!("Got {} bytes", response.content_length().unwrap());
+    let page = client.get(&download_url)?;
+    let articles = puzzle::articles(&page);
+    if articles.is_empty() {
+        return Err(format!("No puzzle description found at '{download_url}'").into());
+    }
 
-    let input_text = response.text()?;
-    fs::write(&output_path, input_text)?;
+    let markdown = articles
+        .iter()
+        .map(|article| puzzle::to_markdown(article))
+        .collect::<Vec<_>>()
+        .join("\n");
+    fs::write(&description_file, markdown)?;
+    println!("Wrote {}", description_file.to_str().unwrap());
+
+    // Part 2 very rarely introduces new example inputs, but it does happen; so, number the
+    // examples across both parts.
+    let examples = articles.iter().flat_map(|article| puzzle::examples(article));
+    for (i, example) in examples.enumerate() {
+        let example_file = input_dir.join(format!("{day}.example.{}", i + 1));
+        fs::write(&example_file, example)?;
+        println!("Wrote {}", example_file.to_str().unwrap());
+    }
 
-    println!("Wrote {}", output_path.to_str().unwrap());
+    let answers = puzzle::answers(&page);
+    if !answers.is_empty() {
+        fs::write(&answers_file, answers.join("\n") + "\n")?;
+        println!("Wrote {}", answers_file.to_str().unwrap());
+    }
 
     Ok(())
 }
@@ -0,0 +1,260 @@
+//! Module to convert the HTML puzzle descriptions served by Advent of Code into Markdown that can
+//! be read offline, and to pull the example blocks out of those descriptions.
+
+use regex::Regex;
+
+/// Find the HTML body of each `<article class="day-desc">` on a puzzle page. Part 1 is always
+/// present; part 2 is only present once it has been unlocked for the session used to fetch the
+/// page.
+pub fn articles(page: &str) -> Vec<&str> {
+    let re = Regex::new(r#"(?s)<article class="day-desc">(.*?)</article>"#).unwrap();
+    re.captures_iter(page)
+        .filter_map(|cap| cap.get(1))
+        .map(|m| m.as_str())
+        .collect()
+}
+
+/// Extract the contents of every `<pre><code>` block in the given HTML. These are (almost always)
+/// the example inputs given in a puzzle description.
+pub fn examples(html: &str) -> Vec<String> {
+    let re = Regex::new(r"(?s)<pre><code>(.*?)</code></pre>").unwrap();
+    re.captures_iter(html)
+        .filter_map(|cap| cap.get(1))
+        .map(|m| decode_entities(&strip_tags(m.as_str())))
+        .collect()
+}
+
+/// Find the answers to any parts of the puzzle which have already been solved; these are shown on
+/// the puzzle page (outside of the descriptions) once each part is solved.
+pub fn answers(page: &str) -> Vec<String> {
+    let re = Regex::new(r"Your puzzle answer was <code>(.*?)</code>").unwrap();
+    re.captures_iter(page)
+        .filter_map(|cap| cap.get(1))
+        .map(|m| decode_entities(m.as_str()))
+        .collect()
+}
+
+/// Convert the HTML of a puzzle description into Markdown.
+///
+/// This only handles the (small) subset of HTML that Advent of Code actually uses in its puzzle
+/// descriptions; any other tags are dropped, leaving just their text.
+pub fn to_markdown(html: &str) -> String {
+    let mut md = String::new();
+
+    // Whether we're inside a `<pre>` block (where whitespace is significant and no other
+    // formatting applies) or an inline `<code>` span (where Markdown emphasis isn't rendered).
+    let mut in_pre = false;
+    let mut in_code = false;
+
+    // Targets of any `<a>` tags we're currently inside of; these are written when the tag closes.
+    let mut links: Vec<String> = Vec::new();
+
+    let mut rest = html;
+    while !rest.is_empty() {
+        let (text, tag) = match rest.find('<') {
+            Some(0) => {
+                let end = rest.find('>').map(|i| i + 1).unwrap_or(rest.len());
+                let tag = &rest[..end];
+                rest = &rest[end..];
+                ("", Some(tag))
+            }
+            Some(i) => {
+                let text = &rest[..i];
+                rest = &rest[i..];
+                (text, None)
+            }
+            None => {
+                let text = rest;
+                rest = "";
+                (text, None)
+            }
+        };
+
+        if !text.is_empty() {
+            let text = decode_entities(text);
+            if in_pre {
+                md.push_str(&text);
+            } else {
+                let text = collapse_whitespace(&text);
+
+                // Skip the whitespace between block elements; it would otherwise indent the
+                // start of the following block.
+                let at_line_start = md.is_empty() || md.ends_with('\n');
+                if !(at_line_start && text.trim().is_empty()) {
+                    md.push_str(&text);
+                }
+            }
+        }
+
+        let tag = match tag {
+            Some(tag) => Tag::parse(tag),
+            None => continue,
+        };
+
+        match (tag.name.as_str(), tag.closing) {
+            ("h2", false) => md.push_str("## "),
+            ("h2", true) | ("p", true) => md.push_str("\n\n"),
+            ("pre", false) => {
+                in_pre = true;
+                md.push_str("```\n");
+            }
+            ("pre", true) => {
+                in_pre = false;
+                if !md.ends_with('\n') {
+                    md.push('\n');
+                }
+                md.push_str("```\n\n");
+            }
+            ("code", closing) if !in_pre => {
+                in_code = !closing;
+                md.push('`');
+            }
+            ("em", _) if !in_pre && !in_code => md.push_str("**"),
+            ("a", false) => {
+                links.push(tag.attr("href").unwrap_or_default());
+                md.push('[');
+            }
+            ("a", true) => {
+                let href = links.pop().unwrap_or_default();
+                md.push_str(&format!("]({href})"));
+            }
+            ("li", false) => md.push_str("- "),
+            ("li", true) => md.push('\n'),
+            ("ul", true) => md.push('\n'),
+            ("br", _) => md.push('\n'),
+            _ => {}
+        }
+    }
+
+    // Tidy up the trailing whitespace left over from the final block element
+    let mut md = md.trim_end().to_string();
+    md.push('\n');
+    md
+}
+
+/// A single (opening or closing) HTML tag
+struct Tag {
+    name: String,
+    closing: bool,
+    attrs: String,
+}
+
+impl Tag {
+    /// Parse a tag like `<a href="/2024/day/1/input" target="_blank">` or `</a>`.
+    fn parse(s: &str) -> Self {
+        let s = s.trim_start_matches('<').trim_end_matches('>');
+        let s = s.trim_end_matches('/');
+        let (closing, s) = match s.strip_prefix('/') {
+            Some(s) => (true, s),
+            None => (false, s),
+        };
+        let (name, attrs) = s.split_once(char::is_whitespace).unwrap_or((s, ""));
+
+        Self {
+            name: name.to_lowercase(),
+            closing,
+            attrs: attrs.to_string(),
+        }
+    }
+
+    /// Get the (decoded) value of the given attribute, if it is present.
+    fn attr(&self, name: &str) -> Option<String> {
+        let re = Regex::new(&format!(r#"{name}="([^"]*)""#)).unwrap();
+        re.captures(&self.attrs)
+            .and_then(|cap| cap.get(1))
+            .map(|m| decode_entities(m.as_str()))
+    }
+}
+
+/// Remove all HTML tags from a string, leaving only their text.
+fn strip_tags(html: &str) -> String {
+    let re = Regex::new(r"<[^>]*>").unwrap();
+    re.replace_all(html, "").into_owned()
+}
+
+/// Replace any runs of whitespace which include a newline with a single space; outside of `<pre>`
+/// blocks, line breaks in the HTML source aren't meaningful.
+fn collapse_whitespace(text: &str) -> String {
+    let re = Regex::new(r"\s*\n\s*").unwrap();
+    re.replace_all(text, " ").into_owned()
+}
+
+/// Decode the HTML character references that show up in puzzle descriptions.
+fn decode_entities(text: &str) -> String {
+    let re = Regex::new(r"&(#x[0-9a-fA-F]+|#[0-9]+|[a-zA-Z]+);").unwrap();
+    re.replace_all(text, |cap: &regex::Captures| {
+        let entity = &cap[1];
+        let decoded = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ if entity.starts_with("#x") => u32::from_str_radix(&entity[2..], 16)
+                .ok()
+                .and_then(char::from_u32),
+            _ if entity.starts_with('#') => entity[1..].parse().ok().and_then(char::from_u32),
+            _ => None,
+        };
+
+        decoded
+            .map(String::from)
+            .unwrap_or_else(|| cap[0].to_string())
+    })
+    .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 1: Test ---</h2><p>Find the <em>total</em> of the <a href="https://example.com" target="_blank">list</a>:</p>
+<pre><code>3   4
+<em>4</em>   3
+</code></pre>
+<ul>
+<li>Compare <code>a &lt; b</code>.</li>
+</ul>
+</article>
+<p>Your puzzle answer was <code>11</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2><p>Now multiply.</p>
+</article>
+</main>"#;
+
+    #[test]
+    fn find_articles() {
+        let articles = articles(PAGE);
+        assert_eq!(articles.len(), 2);
+        assert!(articles[0].starts_with("<h2>--- Day 1: Test ---</h2>"));
+        assert!(articles[1].contains("Now multiply."));
+    }
+
+    #[test]
+    fn find_examples() {
+        assert_eq!(examples(PAGE), vec!["3   4\n4   3\n"]);
+    }
+
+    #[test]
+    fn find_answers() {
+        assert_eq!(answers(PAGE), vec!["11"]);
+    }
+
+    #[test]
+    fn convert_markdown() {
+        let expected = r#"## --- Day 1: Test ---
+
+Find the **total** of the [list](https://example.com):
+
+```
+3   4
+4   3
+```
+
+- Compare `a < b`.
+"#;
+
+        assert_eq!(to_markdown(articles(PAGE)[0]), expected);
+    }
+}
@@ -0,0 +1,170 @@
+//! Module for loading, storing, and checking the Advent of Code session cookie used to fetch
+//! puzzle inputs & descriptions.
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use regex::Regex;
+
+use crate::client::Client;
+use crate::date::date_str;
+
+/// Environment variable which, if set, takes precedence over the session cookie file
+pub const ENV_VAR: &str = "AOC_SESSION";
+
+/// Page to request to check whether a session is valid; this is small and, when the session is
+/// valid, includes the name of the logged in user.
+const CHECK_URL: &str = "https://adventofcode.com/settings";
+
+/// Advent of Code doesn't tell us when a session expires, but they've historically lasted about a
+/// month; so, use that to estimate when it's time to log in again.
+const ASSUMED_LIFETIME: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Load the session cookie from the [`ENV_VAR`] environment variable or, if that isn't set, from
+/// the given file.
+pub fn load(path: &Path) -> Result<String, Box<dyn Error>> {
+    if let Ok(cookie) = std::env::var(ENV_VAR) {
+        return normalize(&cookie).map_err(|e| format!("Invalid {ENV_VAR}: {e}").into());
+    }
+
+    if !(path.exists() && path.is_file()) {
+        return Err(format!(
+            "No such session cookie file: {} (and {ENV_VAR} is not set)",
+            path.to_str()
+                .unwrap_or("err: path contains non-unicode data")
+        )
+        .into());
+    }
+
+    warn_if_world_readable(path)?;
+
+    let cookie = fs::read_to_string(path)?;
+    normalize(&cookie).map_err(|e| format!("Invalid session cookie file: {e}").into())
+}
+
+/// Validate the format of a session cookie and write it to the given file, readable only by the
+/// current user.
+pub fn store(path: &Path, cookie: &str) -> Result<(), Box<dyn Error>> {
+    let cookie = normalize(cookie)?;
+
+    // Create the file with restrictive permissions from the outset, rather than tightening them
+    // after the cookie has been written.
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)?;
+
+        // The mode above only applies to newly created files, so fix up any existing one too.
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        file.write_all(cookie.as_bytes())?;
+    }
+    #[cfg(not(unix))]
+    fs::write(path, &cookie)?;
+
+    Ok(())
+}
+
+/// Check that the session is accepted by the Advent of Code site, returning the name of the
+/// logged in user if it is.
+pub fn validate(client: &Client) -> Result<String, Box<dyn Error>> {
+    let page = client.get(CHECK_URL)?;
+
+    let re = Regex::new(r#"<div class="user">([^<]*)"#)?;
+    re.captures(&page)
+        .and_then(|cap| cap.get(1))
+        .map(|m| m.as_str().trim().to_string())
+        .ok_or_else(|| "Session was not accepted; you may need to log in again".into())
+}
+
+/// Estimate when the session stored in the given file expires, based on when it was written.
+fn expiry(path: &Path) -> Result<(SystemTime, SystemTime), Box<dyn Error>> {
+    let stored = fs::metadata(path)?.modified()?;
+    Ok((stored, stored + ASSUMED_LIFETIME))
+}
+
+/// Describe the (estimated) expiry of the session stored in the given file.
+pub fn expiry_str(path: &Path) -> Result<String, Box<dyn Error>> {
+    let (stored, expires) = expiry(path)?;
+
+    let status = if expires > SystemTime::now() {
+        "expires around"
+    } else {
+        "probably expired around"
+    };
+
+    Ok(format!(
+        "Session cookie stored on {}; it {status} {}",
+        date_str(stored),
+        date_str(expires)
+    ))
+}
+
+/// Trim any surrounding whitespace (e.g., a trailing newline) from a session cookie, along with a
+/// `session=` prefix if the whole cookie was copied from the browser, then check that what's left
+/// looks like a session token.
+fn normalize(cookie: &str) -> Result<String, String> {
+    let cookie = cookie.trim();
+    let cookie = cookie.strip_prefix("session=").unwrap_or(cookie);
+
+    if cookie.is_empty() {
+        return Err("session cookie is empty".into());
+    }
+
+    if !cookie.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("session cookie should only contain hexadecimal digits".into());
+    }
+
+    Ok(cookie.to_string())
+}
+
+/// Print a warning if the given file can be read by other users.
+fn warn_if_world_readable(path: &Path) -> Result<(), Box<dyn Error>> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = fs::metadata(path)?.permissions().mode();
+        if mode & 0o004 != 0 {
+            eprintln!(
+                "Warning: session cookie file {} is world-readable; consider running `chmod 600` on it",
+                path.display()
+            );
+        }
+    }
+
+    #[cfg(not(unix))]
+    let _ = path;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_cookie() {
+        let cases = vec![
+            ("53616c7465645f5f\n", Ok("53616c7465645f5f")),
+            ("  session=53616c7465645f5f ", Ok("53616c7465645f5f")),
+            ("\n", Err(())),
+            ("53616c74 65645f5f", Err(())),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                normalize(input).map_err(|_| ()),
+                expected.map(String::from),
+                "{input:?}"
+            );
+        }
+    }
+}
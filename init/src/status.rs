@@ -0,0 +1,192 @@
+//! Module to summarize progress across all of the puzzle years in the workspace: which days have
+//! solvers (and whether those solvers are actually implemented), which inputs have been fetched,
+//! and which answers have been recorded.
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use regex::Regex;
+
+/// Markers left in solvers which haven't been (fully) implemented; e.g., from the `dXX.rs`
+/// template, or for parts too slow to run every time.
+const PLACEHOLDERS: [&str; 2] = ["todo!(", "-- skipped --"];
+
+/// The status of a single day within a puzzle year
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct DayStatus {
+    /// Whether there is a `src/days/dXX.rs` solver for the day
+    solver: bool,
+
+    /// Whether each part of the solver is implemented
+    parts: (bool, bool),
+
+    /// Whether the input for the day has been fetched
+    input: bool,
+
+    /// The number of answers recorded for the day (see `init fetch --description`)
+    answers: usize,
+}
+
+impl DayStatus {
+    fn stars(&self) -> usize {
+        usize::from(self.parts.0) + usize::from(self.parts.1)
+    }
+
+    /// The character representing this day in the calendar grid
+    fn symbol(&self) -> char {
+        match (self.solver, self.parts) {
+            (false, _) => '.',
+            (true, (true, true)) => '*',
+            (true, (true, false)) | (true, (false, true)) => '+',
+            (true, (false, false)) => '-',
+        }
+    }
+}
+
+/// Render the progress across all of the puzzle years in the given workspace.
+pub fn status(workspace_root: &Path) -> Result<String, Box<dyn Error>> {
+    let crate_regex = Regex::new(r"^aoc(\d{4})$")?;
+    let mut years: Vec<u16> = fs::read_dir(workspace_root)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let cap = crate_regex.captures(name.to_str()?)?;
+            cap[1].parse().ok()
+        })
+        .collect();
+    years.sort_unstable();
+
+    // Label each day's column vertically; e.g., day 12 is a `1` above a `2`.
+    let tens: String = (1..=25u8)
+        .map(|day| match day {
+            1..=9 => ' ',
+            _ => char::from(b'0' + day / 10),
+        })
+        .collect();
+    let ones: String = (1..=25u8).map(|day| char::from(b'0' + day % 10)).collect();
+
+    let mut grid = format!("{:5}{}\n{:5}{ones}\n", "", tens.trim_end(), "");
+    let mut notes = String::new();
+
+    for year in years {
+        let crate_root = workspace_root.join(format!("aoc{year}"));
+        let days = (1..=25)
+            .map(|day| day_status(&crate_root, day))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let symbols: String = days.iter().map(|d| d.symbol()).collect();
+        let stars: usize = days.iter().map(|d| d.stars()).sum();
+        grid.push_str(&format!("{year} {symbols} {stars:>2}\n"));
+
+        let numbered = || days.iter().zip(1..=25);
+        let list = |days: Vec<u8>| {
+            days.iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let unimplemented: Vec<_> = numbered()
+            .filter(|(d, _)| d.solver && d.stars() < 2)
+            .map(|(_, day)| day)
+            .collect();
+        let missing_inputs: Vec<_> = numbered()
+            .filter(|(d, _)| d.solver && !d.input)
+            .map(|(_, day)| day)
+            .collect();
+        let answers: usize = days.iter().map(|d| d.answers).sum();
+
+        notes.push_str(&format!("{year}: {stars} stars, {answers} answers recorded\n"));
+        if !unimplemented.is_empty() {
+            notes.push_str(&format!(
+                "  placeholders on days: {}\n",
+                list(unimplemented)
+            ));
+        }
+        if !missing_inputs.is_empty() {
+            notes.push_str(&format!(
+                "  missing inputs for days: {}\n",
+                list(missing_inputs)
+            ));
+        }
+    }
+
+    Ok(format!(
+        "{grid}\n{notes}\n* both parts solved, + one part solved, - not yet solved, . no solver\n"
+    ))
+}
+
+/// Inspect the solver, input, and recorded answers for a single day.
+fn day_status(crate_root: &Path, day: u8) -> Result<DayStatus, Box<dyn Error>> {
+    let solver_path = crate_root.join(format!("src/days/d{day:0>2}.rs"));
+    let input_path = crate_root.join(format!("input/{day}"));
+    let answers_path = crate_root.join(format!("input/{day}.answers"));
+
+    let mut status = DayStatus::default();
+
+    if solver_path.is_file() {
+        status.solver = true;
+        status.parts = implemented_parts(&fs::read_to_string(solver_path)?);
+    }
+
+    status.input = input_path.is_file();
+
+    if answers_path.is_file() {
+        status.answers = fs::read_to_string(answers_path)?.lines().count();
+    }
+
+    Ok(status)
+}
+
+/// Check which of the `part1` and `part2` functions of a solver are implemented (i.e., don't
+/// contain any placeholders). Anything after `part2` (e.g., helpers and tests) is ignored, as are
+/// commented-out lines.
+fn implemented_parts(source: &str) -> (bool, bool) {
+    let code: Vec<&str> = source
+        .lines()
+        .filter(|l| !l.trim_start().starts_with("//"))
+        .collect();
+    let code = code.join("\n");
+
+    let p1_start = code.find("fn part1");
+    let p2_start = code.find("fn part2");
+
+    let body = |start: Option<usize>| -> Option<&str> {
+        let start = start?;
+        let rest = &code[start..];
+        // The body ends at the first unindented closing brace
+        let end = rest.find("\n}").map(|i| i + 2).unwrap_or(rest.len());
+        Some(&rest[..end])
+    };
+    let implemented = |body: Option<&str>| match body {
+        Some(body) => !PLACEHOLDERS.iter().any(|p| body.contains(p)),
+        None => false,
+    };
+
+    (implemented(body(p1_start)), implemented(body(p2_start)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solver_parts() {
+        let cases = vec![
+            (crate::templates::DAY, (false, false)),
+            (
+                "pub fn part1(input: &str) -> PuzzleResult {\n    Ok(input.len().to_string())\n}\n\npub fn part2(_: &str) -> PuzzleResult {\n    // todo!()\n    Ok(\"-- skipped --\".into())\n}\n",
+                (true, false),
+            ),
+            (
+                "pub fn part1(input: &str) -> PuzzleResult {\n    Ok(input.len().to_string())\n}\n\npub fn part2(input: &str) -> PuzzleResult {\n    // todo!()\n    Ok(input.trim().to_string())\n}\n\n#[test]\nfn p1() {\n    todo!()\n}\n",
+                (true, true),
+            ),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(implemented_parts(source), expected);
+        }
+    }
+}
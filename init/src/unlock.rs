@@ -0,0 +1,70 @@
+//! Module to work out when puzzles unlock so that we never request a puzzle (or its input) before
+//! it is available.
+
+use std::error::Error;
+use std::io::{stdout, Write};
+use std::thread::sleep;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::date::{days_from_civil, duration_str};
+
+/// The first year Advent of Code ran
+pub const FIRST_YEAR: u16 = 2015;
+
+/// Puzzles unlock at midnight US Eastern time, which is always UTC-5 during December
+const UNLOCK_UTC_OFFSET_SECS: u64 = 5 * 60 * 60;
+
+/// Get the time at which the given puzzle unlocks.
+pub fn unlock_time(year: u16, day: u8) -> SystemTime {
+    let days = days_from_civil(year as i64, 12, day as i64);
+    let secs = days as u64 * 24 * 60 * 60 + UNLOCK_UTC_OFFSET_SECS;
+
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+/// Check that the given puzzle has unlocked. If it hasn't, either return an error or, if `wait`
+/// is set, count down until it does.
+pub fn ensure_unlocked(year: u16, day: u8, wait: bool) -> Result<(), Box<dyn Error>> {
+    let unlock = unlock_time(year, day);
+    let Ok(remaining) = unlock.duration_since(SystemTime::now()) else {
+        // The unlock time is in the past
+        return Ok(());
+    };
+
+    if !wait {
+        return Err(format!(
+            "Puzzle {year} day {day} does not unlock for another {}",
+            duration_str(remaining)
+        )
+        .into());
+    }
+
+    // Count down one second at a time (re-checking the clock each time so we don't drift), then
+    // give the servers a moment to catch up before any requests are made.
+    while let Ok(remaining) = unlock.duration_since(SystemTime::now()) {
+        print!(
+            "\rPuzzle {year} day {day} unlocks in {}  ",
+            duration_str(remaining)
+        );
+        let _ = stdout().flush();
+        sleep(remaining.min(Duration::from_secs(1)));
+    }
+    println!("\rPuzzle {year} day {day} is unlocked!{}", " ".repeat(20));
+    sleep(Duration::from_secs(1));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlock_times() {
+        let cases = vec![((2015, 1), 1448946000), ((2024, 1), 1733029200)];
+        for ((year, day), expected) in cases {
+            let expected = UNIX_EPOCH + Duration::from_secs(expected);
+            assert_eq!(unlock_time(year, day), expected);
+        }
+    }
+}
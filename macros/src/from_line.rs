@@ -0,0 +1,438 @@
+//! The implementation of `#[derive(FromLine)]`. Like [days](crate::days), the generated code is
+//! built as a string; the struct is picked apart directly from its tokens.
+
+use proc_macro::{Delimiter, Spacing, TokenStream, TokenTree};
+
+/// A part of a format string
+#[derive(Debug, PartialEq)]
+enum Segment {
+    /// Text which must appear in the line exactly
+    Literal(String),
+
+    /// The name of the field which goes here
+    Field(String),
+}
+
+/// A field of the struct being derived
+#[derive(Debug)]
+struct Field {
+    name: String,
+    ty: String,
+
+    /// From `#[sep("...")]`: the field is a list of items with this separator
+    sep: Option<String>,
+
+    /// From `#[try_from]`: parse the field (or its items) with `TryFrom<&str>` rather than
+    /// `FromStr`
+    try_from: bool,
+}
+
+pub fn derive(item: TokenStream) -> TokenStream {
+    let code = match generate(item) {
+        Ok(code) => code,
+        Err(msg) => format!("compile_error!({msg:?});"),
+    };
+
+    code.parse().unwrap()
+}
+
+fn generate(item: TokenStream) -> Result<String, String> {
+    let mut tokens = item.into_iter().peekable();
+    let mut format = None;
+    let mut name = None;
+    let mut fields = None;
+
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(p) if p.as_char() == '#' => {
+                if let Some(TokenTree::Group(attr)) = tokens.next() {
+                    match attribute(attr.stream()) {
+                        Some((name, args)) if name == "format" => {
+                            format = Some(string_literal(args)?)
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            TokenTree::Ident(i) if i.to_string() == "enum" || i.to_string() == "union" => {
+                return Err("FromLine can only be derived for structs".into());
+            }
+            TokenTree::Ident(i) if i.to_string() == "struct" => {
+                name = match tokens.next() {
+                    Some(TokenTree::Ident(name)) => Some(name.to_string()),
+                    _ => return Err("Expected a struct name".into()),
+                };
+                fields = match tokens.next() {
+                    Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Brace => {
+                        Some(parse_fields(g.stream())?)
+                    }
+                    Some(TokenTree::Punct(p)) if p.as_char() == '<' => {
+                        return Err("FromLine doesn't support generic structs".into());
+                    }
+                    _ => return Err("FromLine needs a struct with named fields".into()),
+                };
+            }
+            _ => {}
+        }
+    }
+
+    let name = name.ok_or("Expected a struct")?;
+    let fields = fields.ok_or("Expected a struct with named fields")?;
+    let format = format.ok_or("FromLine needs a `#[format(\"...\")]` attribute")?;
+
+    let segments = parse_format(&format)?;
+    check_fields(&segments, &fields)?;
+
+    Ok(impl_try_from(&name, &format, &segments, &fields))
+}
+
+/// Split an attribute (the tokens inside `#[...]`) into its name and the tokens of its arguments,
+/// if it has any
+fn attribute(stream: TokenStream) -> Option<(String, TokenStream)> {
+    let mut tokens = stream.into_iter();
+    let name = match tokens.next()? {
+        TokenTree::Ident(name) => name.to_string(),
+        _ => return None,
+    };
+    let args = match tokens.next() {
+        Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Parenthesis => g.stream(),
+        _ => TokenStream::new(),
+    };
+
+    Some((name, args))
+}
+
+/// Get the value of a string literal (e.g., the argument to `#[format("...")]`)
+fn string_literal(stream: TokenStream) -> Result<String, String> {
+    let mut tokens = stream.into_iter();
+    match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Literal(lit)), None) => unescape(&lit.to_string()),
+        _ => Err("Expected a single string literal".into()),
+    }
+}
+
+/// Get the value of a string literal from its source; e.g., `"a\"b"` is `a"b`
+fn unescape(lit: &str) -> Result<String, String> {
+    if let Some(raw) = lit.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        return Ok(raw[hashes + 1..raw.len() - hashes - 1].to_string());
+    }
+
+    let inner = lit
+        .strip_prefix('"')
+        .and_then(|l| l.strip_suffix('"'))
+        .ok_or_else(|| format!("Expected a string literal, found {lit}"))?;
+
+    let mut value = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('t') => value.push('\t'),
+            Some('r') => value.push('\r'),
+            Some('0') => value.push('\0'),
+            Some(c @ ('\\' | '"' | '\'')) => value.push(c),
+            other => return Err(format!("Unsupported escape in format string: \\{other:?}")),
+        }
+    }
+
+    Ok(value)
+}
+
+/// Parse the fields of a struct (the tokens inside its braces)
+fn parse_fields(stream: TokenStream) -> Result<Vec<Field>, String> {
+    let mut fields = Vec::new();
+    let mut field: Vec<TokenTree> = Vec::new();
+    let mut depth = 0;
+
+    // Split on commas which aren't inside a generic type (e.g., `HashMap<K, V>`)
+    for token in stream
+        .into_iter()
+        .chain([TokenTree::from(proc_macro::Punct::new(',', Spacing::Alone))])
+    {
+        match &token {
+            TokenTree::Punct(p) if p.as_char() == '<' => depth += 1,
+            TokenTree::Punct(p) if p.as_char() == '>' => depth -= 1,
+            TokenTree::Punct(p) if p.as_char() == ',' && depth == 0 => {
+                if !field.is_empty() {
+                    fields.push(parse_field(std::mem::take(&mut field))?);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        field.push(token);
+    }
+
+    Ok(fields)
+}
+
+fn parse_field(tokens: Vec<TokenTree>) -> Result<Field, String> {
+    let mut sep = None;
+    let mut try_from = false;
+    let mut tokens = tokens.into_iter().peekable();
+
+    // Attributes (including doc comments) & visibility
+    loop {
+        match tokens.peek() {
+            Some(TokenTree::Punct(p)) if p.as_char() == '#' => {
+                tokens.next();
+                if let Some(TokenTree::Group(attr)) = tokens.next() {
+                    match attribute(attr.stream()) {
+                        Some((name, args)) if name == "sep" => sep = Some(string_literal(args)?),
+                        Some((name, _)) if name == "try_from" => try_from = true,
+                        _ => {}
+                    }
+                }
+            }
+            Some(TokenTree::Ident(i)) if i.to_string() == "pub" => {
+                tokens.next();
+                if let Some(TokenTree::Group(g)) = tokens.peek() {
+                    if g.delimiter() == Delimiter::Parenthesis {
+                        tokens.next();
+                    }
+                }
+            }
+            _ => break,
+        }
+    }
+
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(name)) => name.to_string(),
+        other => return Err(format!("Expected a field name, found {other:?}")),
+    };
+    match tokens.next() {
+        Some(TokenTree::Punct(p)) if p.as_char() == ':' => {}
+        _ => return Err(format!("Expected a type for field `{name}`")),
+    }
+    let ty = tokens.collect::<TokenStream>().to_string();
+
+    Ok(Field {
+        name,
+        ty,
+        sep,
+        try_from,
+    })
+}
+
+/// Split a format string into literal text and `{field}` placeholders. Braces are escaped by
+/// doubling them, as with `format!`.
+fn parse_format(format: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = format.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => return Err(format!("Unclosed '{{' in format: {format:?}")),
+                    }
+                }
+                let name = name.trim().to_string();
+                if name.is_empty() {
+                    return Err(format!("Empty placeholder in format: {format:?}"));
+                }
+
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                } else if let Some(Segment::Field(prev)) = segments.last() {
+                    return Err(format!(
+                        "Placeholders `{prev}` and `{name}` need some text between them"
+                    ));
+                }
+                segments.push(Segment::Field(name));
+            }
+            '}' => return Err(format!("Unmatched '}}' in format: {format:?}")),
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+
+    Ok(segments)
+}
+
+/// Check that each field appears in the format exactly once
+fn check_fields(segments: &[Segment], fields: &[Field]) -> Result<(), String> {
+    let placeholders: Vec<&str> = segments
+        .iter()
+        .filter_map(|s| match s {
+            Segment::Field(name) => Some(name.as_str()),
+            Segment::Literal(_) => None,
+        })
+        .collect();
+
+    for name in &placeholders {
+        if !fields.iter().any(|f| f.name == *name) {
+            return Err(format!("No field named `{name}`"));
+        }
+    }
+    for field in fields {
+        match placeholders.iter().filter(|&&p| p == field.name).count() {
+            1 => {}
+            0 => return Err(format!("Field `{}` isn't in the format", field.name)),
+            _ => {
+                return Err(format!(
+                    "Field `{}` is in the format more than once",
+                    field.name
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Generate `TryFrom<&str>` (and `FromStr`) for the struct. Each literal is matched in turn, and
+/// the text before it is (trimmed and) parsed as the preceding field.
+fn impl_try_from(name: &str, format: &str, segments: &[Segment], fields: &[Field]) -> String {
+    let mut body = String::new();
+
+    for (i, segment) in segments.iter().enumerate() {
+        match segment {
+            Segment::Literal(lit) if i == 0 => body.push_str(&format!(
+                "let rest = rest.strip_prefix({lit:?}).ok_or_else(|| format!(\"Expected {{:?}} at the start of: {{:?}}\", {lit:?}, line))?;\n"
+            )),
+            Segment::Literal(_) => {} // matched along with the field before it
+            Segment::Field(field) => {
+                let text = format!("__{field}");
+                match segments.get(i + 1) {
+                    Some(Segment::Literal(lit)) => body.push_str(&format!(
+                        "let ({text}, rest) = rest.split_once({lit:?}).ok_or_else(|| format!(\"Expected {{:?}} after {{:?}} in: {{:?}}\", {lit:?}, &line[..line.len() - rest.len()], line))?;\n"
+                    )),
+                    _ => body.push_str(&format!("let ({text}, rest) = (rest, \"\");\n")),
+                }
+
+                let field = fields.iter().find(|f| f.name == *field).unwrap();
+                let parse = if field.try_from { "__try_from" } else { "__parse" };
+                let value = match &field.sep {
+                    Some(sep) => format!(
+                        "{text}.split({sep:?}).map(str::trim).filter(|s| !s.is_empty()).map(|s| {parse}({name:?}, s, line)).collect::<::std::result::Result<_, _>>()?",
+                        name = field.name,
+                    ),
+                    None => format!("{parse}({name:?}, {text}, line)?", name = field.name),
+                };
+                body.push_str(&format!("let {}: {} = {value};\n", field.name, field.ty));
+            }
+        }
+    }
+
+    let field_names: Vec<&str> = fields.iter().map(|f| f.name.as_str()).collect();
+    format!(
+        "impl ::std::convert::TryFrom<&str> for {name} {{
+            type Error = ::std::boxed::Box<dyn ::std::error::Error>;
+
+            /// Parse a line with the format `{format}`
+            fn try_from(line: &str) -> ::std::result::Result<Self, Self::Error> {{
+                fn __parse<T>(field: &str, s: &str, line: &str) -> ::std::result::Result<T, ::std::string::String>
+                where
+                    T: ::std::str::FromStr,
+                    T::Err: ::std::fmt::Display,
+                {{
+                    s.trim().parse().map_err(|e| format!(\"Invalid `{{field}}` ({{:?}}) in {{line:?}}: {{e}}\", s.trim()))
+                }}
+
+                fn __try_from<'a, T>(field: &str, s: &'a str, line: &str) -> ::std::result::Result<T, ::std::string::String>
+                where
+                    T: ::std::convert::TryFrom<&'a str>,
+                    T::Error: ::std::fmt::Display,
+                {{
+                    T::try_from(s.trim()).map_err(|e| format!(\"Invalid `{{field}}` ({{:?}}) in {{line:?}}: {{e}}\", s.trim()))
+                }}
+
+                let line = line.trim();
+                let rest = line;
+                {body}
+                if !rest.trim().is_empty() {{
+                    return Err(format!(\"Unexpected {{:?}} at the end of: {{:?}}\", rest, line).into());
+                }}
+
+                Ok(Self {{ {fields} }})
+            }}
+        }}
+
+        impl ::std::str::FromStr for {name} {{
+            type Err = ::std::boxed::Box<dyn ::std::error::Error>;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {{
+                Self::try_from(s)
+            }}
+        }}",
+        format = format.replace('`', "'"),
+        fields = field_names.join(", "),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str) -> Field {
+        Field {
+            name: name.into(),
+            ty: "usize".into(),
+            sep: None,
+            try_from: false,
+        }
+    }
+
+    #[test]
+    fn format() {
+        use Segment::*;
+
+        assert_eq!(
+            parse_format("move {cnt} from { src } to {dst}").unwrap(),
+            vec![
+                Literal("move ".into()),
+                Field("cnt".into()),
+                Literal(" from ".into()),
+                Field("src".into()),
+                Literal(" to ".into()),
+                Field("dst".into()),
+            ]
+        );
+        assert_eq!(
+            parse_format("{{{a}}}").unwrap(),
+            vec![Literal("{".into()), Field("a".into()), Literal("}".into())]
+        );
+
+        assert!(parse_format("{a}{b}").is_err());
+        assert!(parse_format("{a").is_err());
+        assert!(parse_format("a}").is_err());
+        assert!(parse_format("{}").is_err());
+    }
+
+    #[test]
+    fn fields() {
+        let fields = [field("a"), field("b")];
+        assert!(check_fields(&parse_format("{a}, {b}").unwrap(), &fields).is_ok());
+        assert!(check_fields(&parse_format("{a}").unwrap(), &fields).is_err());
+        assert!(check_fields(&parse_format("{a}, {b}, {c}").unwrap(), &fields).is_err());
+        assert!(check_fields(&parse_format("{a}, {b}, {a}").unwrap(), &fields).is_err());
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(unescape(r#""a\"b\\c\n""#).unwrap(), "a\"b\\c\n");
+        assert_eq!(unescape(r###"r#"a"b"#"###).unwrap(), "a\"b");
+        assert!(unescape("12").is_err());
+    }
+}
@@ -1,5 +1,7 @@
 use proc_macro::TokenStream;
 
+mod from_line;
+
 /// Given a token stream of day IDs (e.g., `1, 2, 7, 25`), create a `vec!` invocation
 /// containing runners for each of those days.
 #[proc_macro]
@@ -26,3 +28,27 @@ pub fn days(item: TokenStream) -> TokenStream {
 
     format!("vec![{}]", days.join(",")).parse().unwrap()
 }
+
+/// Derive `TryFrom<&str>` (and `FromStr`) for a struct from the format of the lines it's parsed
+/// from; e.g.,
+///
+/// ```ignore
+/// #[derive(FromLine)]
+/// #[format("move {cnt} from {src} to {dst}")]
+/// struct Instruction {
+///     cnt: usize,
+///     src: usize,
+///     dst: usize,
+/// }
+/// ```
+///
+/// Each field is parsed from the (trimmed) text between the literal parts of the format, with
+/// `FromStr`. Mark a field `#[try_from]` to parse it with `TryFrom<&str>` instead, and a `Vec`
+/// field `#[sep(",")]` to parse it as a list of items with the given separator (empty items are
+/// skipped, so `#[sep(" ")]` handles runs of spaces).
+///
+/// Errors say which part of the line didn't match, or which field couldn't be parsed.
+#[proc_macro_derive(FromLine, attributes(format, sep, try_from))]
+pub fn from_line(item: TokenStream) -> TokenStream {
+    from_line::derive(item)
+}
@@ -63,9 +63,7 @@ use utils::Day;
 fn run_days() {{
     let days = days!({});
 
-    for day in days {{
-      day.run().unwrap();
-    }}
+    utils::run(days).unwrap();
 }}
 ",
             day_nums
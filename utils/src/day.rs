@@ -2,6 +2,8 @@ use std::error::Error;
 use std::fs;
 use std::time::{Duration, Instant};
 
+use crate::{input, render, viz};
+
 pub type PuzzleResult = Result<String, Box<dyn Error>>;
 
 pub struct Day {
@@ -20,7 +22,11 @@ pub struct Day {
 
 impl Day {
     pub fn run(&self) -> Result<(), Box<dyn Error>> {
-        let input = to_static_str(fs::read_to_string(self.input)?);
+        let input = input::normalize(&fs::read_to_string(self.input)?);
+        if let Some(problem) = input::verify_checksum(self.input, &input)? {
+            eprintln!("Warning: {problem}");
+        }
+        let input = to_static_str(input);
 
         println!("Day {}:", self.num);
         run_part("Part 1", self.p1, &input)?;
@@ -31,6 +37,45 @@ impl Day {
     }
 }
 
+/// Run each of the days in order, configured by the command-line arguments:
+///
+/// * `--visualize`: have solvers which support it save images of their work (see [render])
+/// * `--animate`: have solvers which support it animate their work in the terminal (see [viz]).
+///   Their timings then include waiting between frames, so they mean nothing.
+/// * `--fps=N`: the frame rate to start animations at (30 by default)
+pub fn run(days: Vec<Day>) -> Result<(), Box<dyn Error>> {
+    let mut animate = false;
+    let mut fps = 30;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--visualize" => render::enable(),
+            "--animate" => animate = true,
+            _ => match arg.strip_prefix("--fps=").map(str::parse) {
+                Some(Ok(n)) if n > 0 => fps = n,
+                Some(_) => return Err(format!("Invalid frame rate: {arg}").into()),
+                None => {
+                    return Err(format!(
+                        "Unknown argument: {arg} (expected --visualize, --animate, or --fps=N)"
+                    )
+                    .into())
+                }
+            },
+        }
+    }
+    if animate {
+        viz::enable(fps);
+    }
+    if render::enabled() {
+        println!("Saving visualizations in {}\n", render::OUTPUT_DIR);
+    }
+
+    for day in days {
+        day.run()?;
+    }
+
+    Ok(())
+}
+
 // probably _not_ how we want to do this...
 fn to_static_str(s: String) -> &'static str {
     Box::leak(s.into_boxed_str())
@@ -0,0 +1,419 @@
+//! Evaluating graphs of named expressions, where each node's value is an operator applied to other
+//! nodes (or constants), as in 2015 day 7's circuit of wires and gates.
+//!
+//! Nodes are interned, so they're referred to by [NodeId]s rather than by name once a graph is
+//! built. Evaluation works through the nodes in topological order rather than recursively, so
+//! arbitrarily deep graphs are fine, and values are memoized until the graph changes.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{self, Display};
+
+use crate::search;
+
+/// The ID of a node in an [ExprGraph]
+pub type NodeId = usize;
+
+/// An operator that computes a node's value from the values of its arguments
+pub trait Operator<V> {
+    fn apply(&self, args: &[V]) -> Result<V, String>;
+}
+
+/// An argument to an [Expr]: either another node, or a constant value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand<V> {
+    Node(NodeId),
+    Const(V),
+}
+
+/// The expression defining a node's value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expr<O, V> {
+    pub op: O,
+    pub args: Vec<Operand<V>>,
+}
+
+/// Why a node couldn't be evaluated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    /// There's no node with this name
+    UnknownNode(String),
+
+    /// This node is used, but never defined
+    Undefined(String),
+
+    /// These nodes depend on each other in a cycle, in order
+    Cycle(Vec<String>),
+
+    /// The operator for this node failed
+    Op { node: String, msg: String },
+}
+
+impl Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownNode(name) => write!(f, "No such node: {name}"),
+            Self::Undefined(name) => write!(f, "Node {name} is used but never defined"),
+            Self::Cycle(names) => {
+                write!(f, "Nodes depend on each other in a cycle: ")?;
+                for name in names {
+                    write!(f, "{name} -> ")?;
+                }
+                write!(f, "{}", names[0])
+            }
+            Self::Op { node, msg } => write!(f, "Unable to evaluate node {node}: {msg}"),
+        }
+    }
+}
+
+impl Error for EvalError {}
+
+/// A graph of named nodes, each defined by an [Expr] over other nodes
+#[derive(Debug, Clone)]
+pub struct ExprGraph<O, V> {
+    names: Vec<String>,
+    ids: HashMap<String, NodeId>,
+    exprs: Vec<Option<Expr<O, V>>>,
+
+    /// Values given to nodes in place of their expressions
+    inputs: HashMap<NodeId, V>,
+
+    /// The values of nodes evaluated since the graph last changed
+    values: Vec<Option<V>>,
+
+    /// Whether any of `values` are known, so there's something to forget when the graph changes
+    evaluated: bool,
+}
+
+impl<O, V> Default for ExprGraph<O, V> {
+    fn default() -> Self {
+        Self {
+            names: Vec::new(),
+            ids: HashMap::new(),
+            exprs: Vec::new(),
+            inputs: HashMap::new(),
+            values: Vec::new(),
+            evaluated: false,
+        }
+    }
+}
+
+impl<O, V> ExprGraph<O, V>
+where
+    O: Operator<V>,
+    V: Copy,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The ID of the node with the given name, adding one (without a definition) if needed
+    pub fn intern(&mut self, name: &str) -> NodeId {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.exprs.push(None);
+        self.values.push(None);
+        id
+    }
+
+    /// The ID of the node with the given name, if there is one
+    pub fn id(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    /// The name of the node with the given ID
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.names[id]
+    }
+
+    /// The number of nodes in the graph, whether they're defined or not
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// The expression defining the given node, if it's been defined
+    pub fn expr(&self, id: NodeId) -> Option<&Expr<O, V>> {
+        self.exprs[id].as_ref()
+    }
+
+    /// Define (or re-define) the named node as `op` applied to `args`
+    pub fn define(&mut self, name: &str, op: O, args: Vec<Operand<V>>) -> NodeId {
+        let id = self.intern(name);
+        self.exprs[id] = Some(Expr { op, args });
+        self.invalidate();
+        id
+    }
+
+    /// Give the named node a fixed value, overriding its expression
+    pub fn set_input(&mut self, name: &str, value: V) -> NodeId {
+        let id = self.intern(name);
+        self.inputs.insert(id, value);
+        self.invalidate();
+        id
+    }
+
+    /// Go back to evaluating the named node from its expression, if it was given a fixed value
+    pub fn clear_input(&mut self, name: &str) {
+        if let Some(id) = self.id(name) {
+            self.inputs.remove(&id);
+            self.invalidate();
+        }
+    }
+
+    /// Evaluate the named node
+    pub fn eval(&mut self, name: &str) -> Result<V, EvalError> {
+        let id = self
+            .id(name)
+            .ok_or_else(|| EvalError::UnknownNode(name.to_string()))?;
+        self.eval_id(id)
+    }
+
+    /// Evaluate the node with the given ID, along with any nodes it depends on which haven't
+    /// been evaluated yet
+    pub fn eval_id(&mut self, id: NodeId) -> Result<V, EvalError> {
+        if let Some(value) = self.values[id] {
+            return Ok(value);
+        }
+
+        let order = search::toposort([id], |&n| self.deps(n)).map_err(|cycle| {
+            EvalError::Cycle(cycle.into_iter().map(|n| self.names[n].clone()).collect())
+        })?;
+
+        for n in order {
+            if self.values[n].is_some() {
+                continue;
+            }
+
+            let value = match (self.inputs.get(&n), &self.exprs[n]) {
+                (Some(&value), _) => value,
+                (None, Some(expr)) => {
+                    // Everything this node depends on comes before it, so has a value by now
+                    let args: Vec<V> = expr
+                        .args
+                        .iter()
+                        .map(|arg| match *arg {
+                            Operand::Node(dep) => self.values[dep].unwrap(),
+                            Operand::Const(value) => value,
+                        })
+                        .collect();
+                    expr.op.apply(&args).map_err(|msg| EvalError::Op {
+                        node: self.names[n].clone(),
+                        msg,
+                    })?
+                }
+                (None, None) => return Err(EvalError::Undefined(self.names[n].clone())),
+            };
+            self.values[n] = Some(value);
+            self.evaluated = true;
+        }
+
+        Ok(self.values[id].unwrap())
+    }
+
+    /// The nodes which must be evaluated before the given one
+    fn deps(&self, id: NodeId) -> Vec<NodeId> {
+        if self.values[id].is_some() || self.inputs.contains_key(&id) {
+            return Vec::new();
+        }
+
+        match &self.exprs[id] {
+            Some(expr) => expr
+                .args
+                .iter()
+                .filter_map(|arg| match *arg {
+                    Operand::Node(dep) => Some(dep),
+                    Operand::Const(_) => None,
+                })
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Forget every value evaluated so far, e.g. since the graph changed
+    fn invalidate(&mut self) {
+        if self.evaluated {
+            self.values.iter_mut().for_each(|v| *v = None);
+            self.evaluated = false;
+        }
+    }
+}
+
+impl<O, V> ExprGraph<O, V>
+where
+    O: Display,
+    V: Display,
+{
+    /// Describe the graph in Graphviz's DOT language, with an edge from each node to the nodes
+    /// using it. Nodes given fixed values are labeled with them, and the rest are labeled with
+    /// their operators (and any constant arguments).
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph {\n");
+
+        for (id, name) in self.names.iter().enumerate() {
+            let label = match (self.inputs.get(&id), &self.exprs[id]) {
+                (Some(value), _) => format!("{name} = {value}"),
+                (None, Some(expr)) => {
+                    let mut label = format!("{name}\\n{}", expr.op);
+                    for arg in &expr.args {
+                        if let Operand::Const(value) = arg {
+                            label.push_str(&format!(" {value}"));
+                        }
+                    }
+                    label
+                }
+                (None, None) => name.clone(),
+            };
+            dot.push_str(&format!("    {id} [label=\"{}\"];\n", escape(&label)));
+        }
+
+        for (id, expr) in self.exprs.iter().enumerate() {
+            // Overridden nodes don't use their arguments
+            if self.inputs.contains_key(&id) {
+                continue;
+            }
+
+            for arg in expr.iter().flat_map(|e| &e.args) {
+                if let Operand::Node(dep) = arg {
+                    dot.push_str(&format!("    {dep} -> {id};\n"));
+                }
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Escape quotes for a DOT string (leaving `\n`s for line breaks alone)
+fn escape(s: &str) -> String {
+    s.replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Arith {
+        Id,
+        Add,
+        Mul,
+        Div,
+    }
+
+    impl Operator<i64> for Arith {
+        fn apply(&self, args: &[i64]) -> Result<i64, String> {
+            match (self, args) {
+                (Arith::Id, [x]) => Ok(*x),
+                (Arith::Add, _) => Ok(args.iter().sum()),
+                (Arith::Mul, _) => Ok(args.iter().product()),
+                (Arith::Div, [_, 0]) => Err("Division by zero".into()),
+                (Arith::Div, [x, y]) => Ok(x / y),
+                _ => Err(format!("Wrong number of arguments for {self:?}: {args:?}")),
+            }
+        }
+    }
+
+    impl Display for Arith {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{self:?}")
+        }
+    }
+
+    use Operand::*;
+
+    /// `y = (x + 1) * x`
+    fn graph() -> ExprGraph<Arith, i64> {
+        let mut g = ExprGraph::new();
+        let x = g.define("x", Arith::Id, vec![Const(3)]);
+        let sum = g.define("sum", Arith::Add, vec![Node(x), Const(1)]);
+        g.define("y", Arith::Mul, vec![Node(sum), Node(x)]);
+        g
+    }
+
+    #[test]
+    fn eval() {
+        let mut g = graph();
+        assert_eq!(g.eval("y"), Ok(12));
+        assert_eq!(g.eval("sum"), Ok(4));
+        assert_eq!(g.len(), 3);
+        assert_eq!(g.name(g.id("sum").unwrap()), "sum");
+
+        // Overriding an input changes everything depending on it, until it's cleared
+        g.set_input("x", 5);
+        assert_eq!(g.eval("y"), Ok(30));
+        g.clear_input("x");
+        assert_eq!(g.eval("y"), Ok(12));
+
+        // As does re-defining a node
+        g.define("x", Arith::Id, vec![Const(-2)]);
+        assert_eq!(g.eval("y"), Ok(2));
+    }
+
+    #[test]
+    fn errors() {
+        let mut g = graph();
+        assert_eq!(g.eval("z"), Err(EvalError::UnknownNode("z".into())));
+
+        let w = g.intern("w");
+        g.define("z", Arith::Add, vec![Node(w)]);
+        assert_eq!(g.eval("z"), Err(EvalError::Undefined("w".into())));
+
+        g.define("q", Arith::Div, vec![Const(1), Const(0)]);
+        let err = g.eval("q").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unable to evaluate node q: Division by zero"
+        );
+
+        // x -> sum -> x
+        let sum = g.id("sum").unwrap();
+        g.define("x", Arith::Id, vec![Node(sum)]);
+        let err = g.eval("y").unwrap_err();
+        assert_eq!(err, EvalError::Cycle(vec!["sum".into(), "x".into()]));
+        assert_eq!(
+            err.to_string(),
+            "Nodes depend on each other in a cycle: sum -> x -> sum"
+        );
+
+        // Overriding a node in the cycle breaks it
+        g.set_input("x", 1);
+        assert_eq!(g.eval("y"), Ok(2));
+    }
+
+    #[test]
+    fn deep() {
+        // Far deeper than a recursive evaluation could go
+        let mut g = ExprGraph::new();
+        let mut prev = g.define("n0", Arith::Id, vec![Const(0)]);
+        for i in 1..=200_000 {
+            prev = g.define(&format!("n{i}"), Arith::Add, vec![Node(prev), Const(1)]);
+        }
+        assert_eq!(g.eval("n200000"), Ok(200_000));
+    }
+
+    #[test]
+    fn dot() {
+        let mut g = graph();
+        g.set_input("x", 5);
+        let expected = r#"digraph {
+    0 [label="x = 5"];
+    1 [label="sum\nAdd 1"];
+    2 [label="y\nMul"];
+    0 -> 1;
+    1 -> 2;
+    0 -> 2;
+}
+"#;
+        assert_eq!(g.to_dot(), expected);
+    }
+}
@@ -0,0 +1,573 @@
+//! Points, directions, and line segments for the puzzles that move things around a plane (or a
+//! space).
+//!
+//! Unlike a [Grid](crate::grid::Grid), where rows count downwards, the y-axis here points up:
+//! moving [`North`](Dir4::North) increases `y`. Use [`Dir4::grid_offset`] to move around a grid
+//! instead.
+
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::grid;
+
+/// The integer types which can be used as coordinates
+pub trait Coord:
+    Copy + Ord + fmt::Debug + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// The absolute difference between two values, which works for unsigned types too
+    fn abs_diff(self, other: Self) -> Self {
+        if self > other {
+            self - other
+        } else {
+            other - self
+        }
+    }
+}
+
+macro_rules! impl_coord {
+    ($($t:ty),*) => {
+        $(
+            impl Coord for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+            }
+        )*
+    };
+}
+
+impl_coord!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// A point (or vector) in 2D space
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A point (or vector) in 3D space
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Coord> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    pub const fn origin() -> Self {
+        Self::new(T::ZERO, T::ZERO)
+    }
+
+    /// The sum of the distances along each axis between two points
+    pub fn manhattan(&self, other: &Self) -> T {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The largest of the distances along each axis between two points; i.e., the number of
+    /// king's moves between them.
+    pub fn chebyshev(&self, other: &Self) -> T {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// Move `n` steps in the given direction
+    pub fn step(self, dir: impl Into<Dir8>, n: T) -> Self
+    where
+        T: Neg<Output = T>,
+    {
+        self + dir.into().delta() * n
+    }
+}
+
+impl<T: Coord> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    pub const fn origin() -> Self {
+        Self::new(T::ZERO, T::ZERO, T::ZERO)
+    }
+
+    /// The sum of the distances along each axis between two points
+    pub fn manhattan(&self, other: &Self) -> T {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    /// The largest of the distances along each axis between two points
+    pub fn chebyshev(&self, other: &Self) -> T {
+        self.x
+            .abs_diff(other.x)
+            .max(self.y.abs_diff(other.y))
+            .max(self.z.abs_diff(other.z))
+    }
+}
+
+/// Implement the element-wise arithmetic operators for a point type
+macro_rules! impl_ops {
+    ($point:ident { $($field:ident),* }) => {
+        impl<T: Coord> Add for $point<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self { $($field: self.$field + rhs.$field),* }
+            }
+        }
+
+        impl<T: Coord> Sub for $point<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self { $($field: self.$field - rhs.$field),* }
+            }
+        }
+
+        impl<T: Coord> AddAssign for $point<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl<T: Coord> SubAssign for $point<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        /// Scale a point (i.e., a vector)
+        impl<T: Coord> Mul<T> for $point<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                Self { $($field: self.$field * rhs),* }
+            }
+        }
+
+        impl<T: Coord + Neg<Output = T>> Neg for $point<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($field: -self.$field),* }
+            }
+        }
+    };
+}
+
+impl_ops!(Point2 { x, y });
+impl_ops!(Point3 { x, y, z });
+
+impl<T: fmt::Display> fmt::Display for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Self { x, y, z }
+    }
+}
+
+/// Grid points become `(col, row)`; note that the y-axis of a grid points down.
+impl From<grid::Point> for Point2<usize> {
+    fn from(p: grid::Point) -> Self {
+        Self::new(p.col, p.row)
+    }
+}
+
+impl From<Point2<usize>> for grid::Point {
+    fn from(p: Point2<usize>) -> Self {
+        Self::new(p.y, p.x)
+    }
+}
+
+/// One of the 4 orthogonal directions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dir4 {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Dir4 {
+    /// Every direction, clockwise from north
+    pub const ALL: [Self; 4] = [Self::North, Self::East, Self::South, Self::West];
+
+    /// The direction 90 degrees to the left (counter-clockwise) of this one
+    pub const fn left(self) -> Self {
+        match self {
+            Self::North => Self::West,
+            Self::West => Self::South,
+            Self::South => Self::East,
+            Self::East => Self::North,
+        }
+    }
+
+    /// The direction 90 degrees to the right (clockwise) of this one
+    pub const fn right(self) -> Self {
+        self.left().reverse()
+    }
+
+    /// The opposite direction
+    pub const fn reverse(self) -> Self {
+        self.left().left()
+    }
+
+    /// A single step in this direction, with north being `+y`
+    pub fn delta<T: Coord + Neg<Output = T>>(self) -> Point2<T> {
+        Dir8::from(self).delta()
+    }
+
+    /// A single step in this direction as a `(row, col)` offset in a [Grid](crate::grid::Grid),
+    /// where north is up (i.e., `-1` rows)
+    pub const fn grid_offset(self) -> (isize, isize) {
+        match self {
+            Self::North => (-1, 0),
+            Self::East => (0, 1),
+            Self::South => (1, 0),
+            Self::West => (0, -1),
+        }
+    }
+}
+
+impl TryFrom<char> for Dir4 {
+    type Error = String;
+
+    /// Parse any of the usual ways puzzles write directions: `U`/`D`/`L`/`R`, `N`/`S`/`E`/`W`, or
+    /// `^`/`v`/`<`/`>`
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c.to_ascii_uppercase() {
+            'U' | 'N' | '^' => Ok(Self::North),
+            'D' | 'S' | 'V' => Ok(Self::South),
+            'R' | 'E' | '>' => Ok(Self::East),
+            'L' | 'W' | '<' => Ok(Self::West),
+            _ => Err(format!("Unknown direction: '{c}'")),
+        }
+    }
+}
+
+impl FromStr for Dir4 {
+    type Err = Box<dyn Error>;
+
+    /// Parse a single-character direction (see [`Dir4::try_from`]), or a word like `up` or
+    /// `North`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Self::try_from(c)?);
+        }
+
+        match s.to_ascii_lowercase().as_str() {
+            "up" | "north" => Ok(Self::North),
+            "down" | "south" => Ok(Self::South),
+            "right" | "east" => Ok(Self::East),
+            "left" | "west" => Ok(Self::West),
+            _ => Err(format!("Unknown direction: '{s}'").into()),
+        }
+    }
+}
+
+/// One of the 8 directions, including diagonals
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dir8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Dir8 {
+    /// Every direction, clockwise from north
+    pub const ALL: [Self; 8] = [
+        Self::North,
+        Self::NorthEast,
+        Self::East,
+        Self::SouthEast,
+        Self::South,
+        Self::SouthWest,
+        Self::West,
+        Self::NorthWest,
+    ];
+
+    /// The direction `n` eighth-turns clockwise from this one
+    const fn turn(self, n: usize) -> Self {
+        Self::ALL[(self as usize + n) % 8]
+    }
+
+    /// The direction 45 degrees to the left (counter-clockwise) of this one
+    pub const fn left45(self) -> Self {
+        self.turn(7)
+    }
+
+    /// The direction 45 degrees to the right (clockwise) of this one
+    pub const fn right45(self) -> Self {
+        self.turn(1)
+    }
+
+    /// The direction 90 degrees to the left (counter-clockwise) of this one
+    pub const fn left(self) -> Self {
+        self.turn(6)
+    }
+
+    /// The direction 90 degrees to the right (clockwise) of this one
+    pub const fn right(self) -> Self {
+        self.turn(2)
+    }
+
+    /// The opposite direction
+    pub const fn reverse(self) -> Self {
+        self.turn(4)
+    }
+
+    /// A single step in this direction, with north being `+y`
+    pub fn delta<T: Coord + Neg<Output = T>>(self) -> Point2<T> {
+        let (one, zero) = (T::ONE, T::ZERO);
+        let (x, y) = match self {
+            Self::North => (zero, one),
+            Self::NorthEast => (one, one),
+            Self::East => (one, zero),
+            Self::SouthEast => (one, -one),
+            Self::South => (zero, -one),
+            Self::SouthWest => (-one, -one),
+            Self::West => (-one, zero),
+            Self::NorthWest => (-one, one),
+        };
+
+        Point2::new(x, y)
+    }
+
+    /// A single step in this direction as a `(row, col)` offset in a [Grid](crate::grid::Grid),
+    /// where north is up (i.e., `-1` rows)
+    pub fn grid_offset(self) -> (isize, isize) {
+        let delta = self.delta::<isize>();
+        (-delta.y, delta.x)
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Self {
+        match dir {
+            Dir4::North => Self::North,
+            Dir4::East => Self::East,
+            Dir4::South => Self::South,
+            Dir4::West => Self::West,
+        }
+    }
+}
+
+impl FromStr for Dir8 {
+    type Err = Box<dyn Error>;
+
+    /// Parse anything a [Dir4] can be parsed from, or a diagonal like `NE` or `UL`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(dir) = s.parse::<Dir4>() {
+            return Ok(dir.into());
+        }
+
+        match s.to_ascii_uppercase().as_str() {
+            "NE" | "UR" => Ok(Self::NorthEast),
+            "SE" | "DR" => Ok(Self::SouthEast),
+            "SW" | "DL" => Ok(Self::SouthWest),
+            "NW" | "UL" => Ok(Self::NorthWest),
+            _ => Err(format!("Unknown direction: '{s}'").into()),
+        }
+    }
+}
+
+/// A horizontal or vertical line segment, including both of its end points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Segment<T> {
+    pub start: Point2<T>,
+    pub end: Point2<T>,
+}
+
+impl<T: Coord> Segment<T> {
+    /// Create a segment between two points; returns `None` if the segment wouldn't be horizontal
+    /// or vertical.
+    pub fn new(start: Point2<T>, end: Point2<T>) -> Option<Self> {
+        (start.x == end.x || start.y == end.y).then_some(Self { start, end })
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        self.start.y == self.end.y
+    }
+
+    /// The number of points on the segment
+    pub fn num_points(&self) -> T {
+        self.start.manhattan(&self.end) + T::ONE
+    }
+
+    /// Every point on the segment, from `start` to `end`
+    pub fn points(&self) -> impl Iterator<Item = Point2<T>> {
+        let (start, end) = (self.start, self.end);
+        let step = |from: T, to: T, i: T| match from.cmp(&to) {
+            Ordering::Less => from + i,
+            Ordering::Equal => from,
+            Ordering::Greater => from - i,
+        };
+
+        let mut i = T::ZERO;
+        let len = self.num_points();
+        std::iter::from_fn(move || {
+            if i >= len {
+                return None;
+            }
+            let p = Point2::new(step(start.x, end.x, i), step(start.y, end.y, i));
+            i = i + T::ONE;
+            Some(p)
+        })
+    }
+
+    /// Whether the point is on the segment
+    pub fn contains(&self, p: &Point2<T>) -> bool {
+        let between = |v: T, a: T, b: T| a.min(b) <= v && v <= a.max(b);
+        between(p.x, self.start.x, self.end.x) && between(p.y, self.start.y, self.end.y)
+    }
+
+    /// The part of this segment which is also on the other segment, if any. For perpendicular
+    /// segments this is a single point; parallel segments may overlap along a stretch.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let bounds = |a: T, b: T| (a.min(b), a.max(b));
+        let (ax, bx) = (
+            bounds(self.start.x, self.end.x),
+            bounds(other.start.x, other.end.x),
+        );
+        let (ay, by) = (
+            bounds(self.start.y, self.end.y),
+            bounds(other.start.y, other.end.y),
+        );
+
+        let x = (ax.0.max(bx.0), ax.1.min(bx.1));
+        let y = (ay.0.max(by.0), ay.1.min(by.1));
+        if x.0 > x.1 || y.0 > y.1 {
+            return None;
+        }
+
+        // Keep the overlap in the same direction as this segment
+        let (start, end) = (Point2::new(x.0, y.0), Point2::new(x.1, y.1));
+        match self.start <= self.end {
+            true => Self::new(start, end),
+            false => Self::new(end, start),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points() {
+        let a = Point2::new(1, -2);
+        let b = Point2::new(-3, 4);
+        assert_eq!(a + b, Point2::new(-2, 2));
+        assert_eq!(a - b, Point2::new(4, -6));
+        assert_eq!(-a * 3, Point2::new(-3, 6));
+        assert_eq!(a.manhattan(&b), 10);
+        assert_eq!(a.chebyshev(&b), 6);
+
+        let (c, d) = (Point3::new(1u32, 5, 3), Point3::new(4, 2, 3));
+        assert_eq!(c.manhattan(&d), 6);
+        assert_eq!(c.chebyshev(&d), 3);
+
+        let p = Point2::<i32>::origin()
+            .step(Dir4::North, 3)
+            .step(Dir8::SouthWest, 2);
+        assert_eq!(p, Point2::new(-2, 1));
+
+        let g = grid::Point::new(1, 2);
+        assert_eq!(Point2::from(g), Point2::new(2, 1));
+        assert_eq!(grid::Point::from(Point2::from(g)), g);
+    }
+
+    #[test]
+    fn directions() {
+        for (s, expected) in [
+            ("U", Dir4::North),
+            ("v", Dir4::South),
+            ("<", Dir4::West),
+            ("E", Dir4::East),
+            ("up", Dir4::North),
+            ("West", Dir4::West),
+        ] {
+            assert_eq!(s.parse::<Dir4>().unwrap(), expected, "{s}");
+        }
+        assert!("X".parse::<Dir4>().is_err());
+        assert!("forward".parse::<Dir4>().is_err());
+
+        assert_eq!("NE".parse::<Dir8>().unwrap(), Dir8::NorthEast);
+        assert_eq!("^".parse::<Dir8>().unwrap(), Dir8::North);
+
+        assert_eq!(Dir4::North.left(), Dir4::West);
+        assert_eq!(Dir4::North.right(), Dir4::East);
+        assert_eq!(Dir4::East.reverse(), Dir4::West);
+        assert_eq!(Dir8::North.left45(), Dir8::NorthWest);
+        assert_eq!(Dir8::NorthWest.right(), Dir8::NorthEast);
+        assert_eq!(Dir8::SouthEast.reverse(), Dir8::NorthWest);
+
+        for dir in Dir4::ALL {
+            let delta = dir.delta::<isize>();
+            assert_eq!(dir.grid_offset(), (-delta.y, delta.x));
+            assert_eq!(Dir8::from(dir).grid_offset(), dir.grid_offset());
+        }
+    }
+
+    #[test]
+    fn segments() {
+        let seg = Segment::new(Point2::new(2, 1), Point2::new(-1, 1)).unwrap();
+        assert!(seg.is_horizontal());
+        assert_eq!(seg.num_points(), 4);
+        assert_eq!(
+            seg.points().collect::<Vec<_>>(),
+            vec![(2, 1), (1, 1), (0, 1), (-1, 1)]
+                .into_iter()
+                .map(Point2::from)
+                .collect::<Vec<_>>()
+        );
+        assert!(seg.contains(&Point2::new(0, 1)));
+        assert!(!seg.contains(&Point2::new(0, 0)));
+        assert!(Segment::new(Point2::new(0, 0), Point2::new(1, 1)).is_none());
+
+        // Perpendicular
+        let cross = Segment::new(Point2::new(0, -5), Point2::new(0, 5)).unwrap();
+        let point = Segment::new(Point2::new(0, 1), Point2::new(0, 1));
+        assert_eq!(seg.intersection(&cross), point);
+
+        // Parallel & overlapping, or not
+        let overlap = Segment::new(Point2::new(0, 1), Point2::new(5, 1)).unwrap();
+        assert_eq!(
+            seg.intersection(&overlap),
+            Segment::new(Point2::new(2, 1), Point2::new(0, 1))
+        );
+        let apart = Segment::new(Point2::new(0, 2), Point2::new(5, 2)).unwrap();
+        assert_eq!(seg.intersection(&apart), None);
+
+        // Unsigned coordinates
+        let seg = Segment::new(Point2::new(3u8, 0), Point2::new(3, 2)).unwrap();
+        assert_eq!(seg.points().last(), Some(Point2::new(3, 2)));
+    }
+}
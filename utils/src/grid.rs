@@ -0,0 +1,513 @@
+//! A fixed-size 2D grid, as used by the many puzzles whose input is a map of characters.
+//!
+//! Cells are addressed by `(row, col)`, either as a tuple or a [Point], with `(0, 0)` in the top
+//! left corner. All methods that take a point but don't return an `Option` (including indexing)
+//! panic if the point is outside of the grid.
+
+use std::error::Error;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+/// The `(row, col)` offsets to each of the 4 orthogonal neighbors of a cell: up, right, down, and
+/// left.
+const DELTAS_4: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// The `(row, col)` offsets to each of the 8 neighbors of a cell, including diagonals; clockwise
+/// from up.
+const DELTAS_8: [(isize, isize); 8] = [
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+];
+
+/// The location of a cell in a [Grid]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Point {
+    pub const fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((row, col): (usize, usize)) -> Self {
+        Self { row, col }
+    }
+}
+
+impl From<Point> for (usize, usize) {
+    fn from(p: Point) -> Self {
+        (p.row, p.col)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.row, self.col)
+    }
+}
+
+/// A rectangular grid of values, stored row by row in a single `Vec`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    rows: usize,
+    cols: usize,
+}
+
+impl<T> Grid<T> {
+    /// Create a grid with every cell set to `value`
+    pub fn new(rows: usize, cols: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![value; rows * cols],
+            rows,
+            cols,
+        }
+    }
+
+    /// Create a grid from a list of rows, which must all be the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, Box<dyn Error>> {
+        let num_rows = rows.len();
+        let cols = rows.first().map_or(0, |row| row.len());
+
+        let mut cells = Vec::with_capacity(num_rows * cols);
+        for (i, row) in rows.into_iter().enumerate() {
+            if row.len() != cols {
+                return Err(
+                    format!("Row {i} has {} columns, but row 0 has {cols}", row.len()).into(),
+                );
+            }
+            cells.extend(row);
+        }
+
+        Ok(Self {
+            cells,
+            rows: num_rows,
+            cols,
+        })
+    }
+
+    /// Parse a map with one character per cell, converting each character with `f`. Whitespace is
+    /// part of the map (many maps have spaces as cells), so only blank lines at the end are
+    /// ignored; every other line must be the same length.
+    pub fn parse_with<F, E>(s: &str, mut f: F) -> Result<Self, Box<dyn Error>>
+    where
+        F: FnMut(char) -> Result<T, E>,
+        E: Into<Box<dyn Error>>,
+    {
+        let mut lines: Vec<_> = s.lines().collect();
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+
+        let rows = lines
+            .into_iter()
+            .map(|l| l.chars().map(&mut f).collect::<Result<Vec<_>, _>>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Into::into)?;
+
+        Self::from_rows(rows)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Whether the point is inside the grid
+    pub fn contains(&self, p: impl Into<Point>) -> bool {
+        let p = p.into();
+        p.row < self.rows && p.col < self.cols
+    }
+
+    pub fn get(&self, p: impl Into<Point>) -> Option<&T> {
+        let p = p.into();
+        self.contains(p)
+            .then(|| &self.cells[p.row * self.cols + p.col])
+    }
+
+    pub fn get_mut(&mut self, p: impl Into<Point>) -> Option<&mut T> {
+        let p = p.into();
+        match self.contains(p) {
+            true => Some(&mut self.cells[p.row * self.cols + p.col]),
+            false => None,
+        }
+    }
+
+    /// Move from a point by the given number of rows and columns, if the result is in the grid
+    pub fn offset(&self, p: impl Into<Point>, d_row: isize, d_col: isize) -> Option<Point> {
+        let p = p.into();
+        let row = p.row.checked_add_signed(d_row)?;
+        let col = p.col.checked_add_signed(d_col)?;
+
+        let p = Point::new(row, col);
+        self.contains(p).then_some(p)
+    }
+
+    /// The (up to) 4 orthogonal neighbors of a point which are inside the grid
+    pub fn neighbors4(&self, p: impl Into<Point>) -> impl Iterator<Item = Point> + '_ {
+        let p = p.into();
+        DELTAS_4
+            .iter()
+            .filter_map(move |&(dr, dc)| self.offset(p, dr, dc))
+    }
+
+    /// The (up to) 8 neighbors of a point, including diagonals, which are inside the grid
+    pub fn neighbors8(&self, p: impl Into<Point>) -> impl Iterator<Item = Point> + '_ {
+        let p = p.into();
+        DELTAS_8
+            .iter()
+            .filter_map(move |&(dr, dc)| self.offset(p, dr, dc))
+    }
+
+    /// All of the values in the grid, row by row
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// All of the points in the grid, row by row
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let cols = self.cols;
+        (0..self.cells.len()).map(move |i| Point::new(i / cols, i % cols))
+    }
+
+    /// All of the values in the grid along with their locations, row by row
+    pub fn enumerate(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    /// Find the first point (row by row) whose value matches the predicate
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.enumerate().find(|(_, v)| predicate(v)).map(|(p, _)| p)
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+        &mut self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// The values in a single column, top to bottom
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.cols, "Column {col} is out of bounds");
+        self.cells.iter().skip(col).step_by(self.cols)
+    }
+
+    /// Each row of the grid, top to bottom
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on a chunk size of 0, even when there's nothing to chunk
+        self.cells.chunks(self.cols.max(1))
+    }
+
+    /// The values in the rectangle with the given (inclusive) corners, along with their locations.
+    /// The rectangle is clipped to the grid.
+    pub fn region(
+        &self,
+        top_left: impl Into<Point>,
+        bottom_right: impl Into<Point>,
+    ) -> impl Iterator<Item = (Point, &T)> {
+        let (rows, cols) = self.clip(top_left.into(), bottom_right.into());
+        self.iter_rows()
+            .enumerate()
+            .skip(rows.start)
+            .take(rows.len())
+            .flat_map(move |(r, row)| {
+                let start = cols.start;
+                row[cols.clone()]
+                    .iter()
+                    .enumerate()
+                    .map(move |(i, v)| (Point::new(r, start + i), v))
+            })
+    }
+
+    /// Mutable access to the values in the rectangle with the given (inclusive) corners, along
+    /// with their locations. The rectangle is clipped to the grid.
+    pub fn region_mut(
+        &mut self,
+        top_left: impl Into<Point>,
+        bottom_right: impl Into<Point>,
+    ) -> impl Iterator<Item = (Point, &mut T)> {
+        let (rows, cols) = self.clip(top_left.into(), bottom_right.into());
+        self.cells
+            .chunks_mut(self.cols.max(1))
+            .enumerate()
+            .skip(rows.start)
+            .take(rows.len())
+            .flat_map(move |(r, row)| {
+                let start = cols.start;
+                row[cols.clone()]
+                    .iter_mut()
+                    .enumerate()
+                    .map(move |(i, v)| (Point::new(r, start + i), v))
+            })
+    }
+
+    /// Get the (exclusive) ranges of rows and columns of a rectangle, clipped to the grid
+    fn clip(
+        &self,
+        top_left: Point,
+        bottom_right: Point,
+    ) -> (std::ops::Range<usize>, std::ops::Range<usize>) {
+        let clip = |start: usize, end: usize, len: usize| {
+            let end = (end + 1).min(len);
+            start.min(end)..end
+        };
+
+        (
+            clip(top_left.row, bottom_right.row, self.rows),
+            clip(top_left.col, bottom_right.col, self.cols),
+        )
+    }
+
+    /// Create a new grid by applying `f` to each value
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            rows: self.rows,
+            cols: self.cols,
+        }
+    }
+
+    /// Create a new grid of the given size, where the value at each point is taken from the given
+    /// point in this grid.
+    fn rearrange(&self, rows: usize, cols: usize, from: impl Fn(Point) -> Point) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..rows * cols)
+            .map(|i| self[from(Point::new(i / cols, i % cols))].clone())
+            .collect();
+
+        Self { cells, rows, cols }
+    }
+
+    /// Swap the rows and columns of the grid
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.rearrange(self.cols, self.rows, |p| Point::new(p.col, p.row))
+    }
+
+    /// Rotate the grid a quarter turn clockwise
+    pub fn rotate_cw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let rows = self.rows;
+        self.rearrange(self.cols, self.rows, |p| {
+            Point::new(rows - 1 - p.col, p.row)
+        })
+    }
+
+    /// Rotate the grid a quarter turn counter-clockwise
+    pub fn rotate_ccw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let cols = self.cols;
+        self.rearrange(self.cols, self.rows, |p| {
+            Point::new(p.col, cols - 1 - p.row)
+        })
+    }
+
+    /// Mirror the grid left-to-right
+    pub fn flip_horizontal(&self) -> Self
+    where
+        T: Clone,
+    {
+        let cols = self.cols;
+        self.rearrange(self.rows, self.cols, |p| {
+            Point::new(p.row, cols - 1 - p.col)
+        })
+    }
+
+    /// Mirror the grid top-to-bottom
+    pub fn flip_vertical(&self) -> Self
+    where
+        T: Clone,
+    {
+        let rows = self.rows;
+        self.rearrange(self.rows, self.cols, |p| {
+            Point::new(rows - 1 - p.row, p.col)
+        })
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = Box<dyn Error>;
+
+    /// Parse a map with one character per cell; see [`Grid::parse_with`]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, Ok::<_, Box<dyn Error>>)
+    }
+}
+
+impl<T, P: Into<Point>> Index<P> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: P) -> &Self::Output {
+        let p = p.into();
+        let (rows, cols) = (self.rows, self.cols);
+        self.get(p)
+            .unwrap_or_else(|| panic!("{p} is outside of the {rows}x{cols} grid"))
+    }
+}
+
+impl<T, P: Into<Point>> IndexMut<P> for Grid<T> {
+    fn index_mut(&mut self, p: P) -> &mut Self::Output {
+        let p = p.into();
+        let (rows, cols) = (self.rows, self.cols);
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("{p} is outside of the {rows}x{cols} grid"))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    /// Render the grid with each row on its own line, and no separators between values (so a
+    /// parsed `Grid<char>` is displayed the same as its input)
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.iter_rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for value in row {
+                write!(f, "{value}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = "abc\ndef\n";
+
+    fn grid() -> Grid<char> {
+        MAP.parse().unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        let g = grid();
+        assert_eq!((g.rows(), g.cols()), (2, 3));
+        assert_eq!(g[(0, 0)], 'a');
+        assert_eq!(g[Point::new(1, 2)], 'f');
+        assert_eq!(g.get((2, 0)), None);
+        assert_eq!(g.to_string(), MAP.trim_end());
+
+        let digits = Grid::parse_with("12\r\n34\r\n", |c| c.to_digit(10).ok_or("not a digit"));
+        assert_eq!(digits.unwrap().iter().sum::<u32>(), 10);
+
+        assert!("ab\nc".parse::<Grid<char>>().is_err());
+        assert!(Grid::parse_with("1x", |c| c.to_digit(10).ok_or("not a digit")).is_err());
+
+        // Spaces are cells, but blank lines at the end aren't rows
+        let spaced: Grid<char> = "  a\n b \n\n\n".parse().unwrap();
+        assert_eq!((spaced.rows(), spaced.cols()), (2, 3));
+        assert_eq!(spaced[(1, 0)], ' ');
+        assert!("ab\n\ncd\n".parse::<Grid<char>>().is_err());
+        assert!(" ab\ncd\n".parse::<Grid<char>>().is_err());
+    }
+
+    #[test]
+    fn neighbors() {
+        let g = Grid::new(3, 3, 0);
+        let cases = vec![
+            ((0, 0), vec![(0, 1), (1, 0)], vec![(0, 1), (1, 1), (1, 0)]),
+            (
+                (1, 1),
+                vec![(0, 1), (1, 2), (2, 1), (1, 0)],
+                vec![
+                    (0, 1),
+                    (0, 2),
+                    (1, 2),
+                    (2, 2),
+                    (2, 1),
+                    (2, 0),
+                    (1, 0),
+                    (0, 0),
+                ],
+            ),
+            ((2, 2), vec![(1, 2), (2, 1)], vec![(1, 2), (2, 1), (1, 1)]),
+        ];
+
+        for (p, expected4, expected8) in cases {
+            let to_points =
+                |v: Vec<(usize, usize)>| v.into_iter().map(Point::from).collect::<Vec<_>>();
+            assert_eq!(g.neighbors4(p).collect::<Vec<_>>(), to_points(expected4));
+            assert_eq!(g.neighbors8(p).collect::<Vec<_>>(), to_points(expected8));
+        }
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let mut g = grid();
+        assert_eq!(g.row(1), ['d', 'e', 'f']);
+        assert_eq!(g.column(1).collect::<String>(), "be");
+        assert_eq!(g.iter_rows().count(), 2);
+        assert_eq!(g.position(|&c| c == 'e'), Some(Point::new(1, 1)));
+
+        g.row_mut(0).fill('x');
+        assert_eq!(g.to_string(), "xxx\ndef");
+    }
+
+    #[test]
+    fn regions() {
+        let mut g = Grid::new(4, 4, 0);
+        for (_, v) in g.region_mut((1, 1), (2, 5)) {
+            *v += 1;
+        }
+        assert_eq!(g.to_string(), "0000\n0111\n0111\n0000");
+
+        let region: Vec<_> = g.region((2, 2), (3, 3)).collect();
+        assert_eq!(
+            region,
+            vec![
+                (Point::new(2, 2), &1),
+                (Point::new(2, 3), &1),
+                (Point::new(3, 2), &0),
+                (Point::new(3, 3), &0),
+            ]
+        );
+
+        assert_eq!(g.region((5, 5), (6, 6)).count(), 0);
+    }
+
+    #[test]
+    fn transforms() {
+        let g = grid();
+        assert_eq!(g.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(g.rotate_cw().to_string(), "da\neb\nfc");
+        assert_eq!(g.rotate_ccw().to_string(), "cf\nbe\nad");
+        assert_eq!(g.flip_horizontal().to_string(), "cba\nfed");
+        assert_eq!(g.flip_vertical().to_string(), "def\nabc");
+        assert_eq!(g.rotate_cw().rotate_ccw(), g);
+        assert_eq!(g.map(|c| c.to_ascii_uppercase()).to_string(), "ABC\nDEF");
+    }
+}
@@ -0,0 +1,224 @@
+//! Searching for the lowest integer suffix of some text whose MD5 digest meets a condition, as in
+//! 2015 day 4 (and 2016 days 5, 14, and 17).
+//!
+//! The search is split into chunks of consecutive suffixes which threads claim in increasing
+//! order, so when one thread finds a match, the others can stop once they've checked everything
+//! below it. Each candidate starts from a copy of the state after hashing the prefix, rather than
+//! re-hashing the prefix every time.
+//!
+//! The puzzles' searches take millions of tries, which is slow in unoptimized builds, so tests
+//! which search that far are [ignored](crate).
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+
+use md5::{Digest, Md5};
+
+/// An MD5 digest
+pub type Hash = [u8; 16];
+
+/// The number of consecutive suffixes a thread checks before claiming more work
+const CHUNK_SIZE: u64 = 4096;
+
+/// Find the lowest integer `n >= from` such that the MD5 digest of `prefix` followed by `n` (in
+/// decimal) satisfies `is_match`, using every available thread. If there's no such `n`, this will
+/// search (for a very long time) until the suffixes overflow, and then panic.
+pub fn lowest_suffix<P>(prefix: &str, from: u64, is_match: P) -> u64
+where
+    P: Fn(&Hash) -> bool + Sync,
+{
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    search(prefix, from, threads, &is_match)
+}
+
+/// Find successive suffixes `n >= from` whose digests satisfy `is_match`, in increasing order
+/// (e.g., for the characters of 2016 day 5's password). Each one is found with [lowest_suffix].
+pub fn suffixes<'a, P>(
+    prefix: &'a str,
+    from: u64,
+    is_match: P,
+) -> impl Iterator<Item = (u64, Hash)> + 'a
+where
+    P: Fn(&Hash) -> bool + Sync + 'a,
+{
+    let mut next = from;
+    std::iter::from_fn(move || {
+        let n = lowest_suffix(prefix, next, &is_match);
+        next = n + 1;
+        Some((n, md5(prefix, n)))
+    })
+}
+
+/// The MD5 digest of `prefix` followed by `n` (in decimal)
+pub fn md5(prefix: &str, n: u64) -> Hash {
+    let mut hasher = Md5::new_with_prefix(prefix);
+    hasher.update(Decimal::new(n).as_bytes());
+    hasher.finalize().into()
+}
+
+/// The number of leading zeroes in the hexadecimal representation of a digest
+pub fn leading_zeroes(hash: &Hash) -> u32 {
+    u128::from_be_bytes(*hash).leading_zeros() / 4
+}
+
+/// The `i`-th hexadecimal digit of a digest
+pub fn nibble(hash: &Hash, i: usize) -> u8 {
+    let byte = hash[i / 2];
+    if i.is_multiple_of(2) {
+        byte >> 4
+    } else {
+        byte & 0xf
+    }
+}
+
+/// The hexadecimal representation of a digest
+pub fn to_hex(hash: &Hash) -> String {
+    hash.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// [lowest_suffix] with a given number of threads. This isn't generic over the condition so
+/// that the hashing is compiled (and optimized) with this crate, rather than with the caller.
+fn search(
+    prefix: &str,
+    from: u64,
+    threads: usize,
+    is_match: &(dyn Fn(&Hash) -> bool + Sync),
+) -> u64 {
+    let base = Md5::new_with_prefix(prefix);
+    let next_chunk = AtomicU64::new(from);
+    let best = AtomicU64::new(u64::MAX);
+
+    thread::scope(|s| {
+        for _ in 0..threads.max(1) {
+            s.spawn(|| loop {
+                let start = next_chunk.fetch_add(CHUNK_SIZE, Ordering::Relaxed);
+                // Chunks are claimed in order, so everything after a match can be skipped
+                if start >= best.load(Ordering::Relaxed) {
+                    break;
+                }
+                let end = start
+                    .checked_add(CHUNK_SIZE)
+                    .expect("Ran out of suffixes to try");
+
+                for n in start..end {
+                    let mut hasher = base.clone();
+                    hasher.update(Decimal::new(n).as_bytes());
+                    if is_match(&hasher.finalize().into()) {
+                        best.fetch_min(n, Ordering::Relaxed);
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    best.into_inner()
+}
+
+/// The decimal representation of a number, without allocating a `String` for it
+struct Decimal {
+    digits: [u8; 20],
+    start: usize,
+}
+
+impl Decimal {
+    fn new(mut n: u64) -> Self {
+        let mut digits = [0; 20];
+        let mut start = digits.len();
+        loop {
+            start -= 1;
+            digits[start] = b'0' + (n % 10) as u8;
+            n /= 10;
+            if n == 0 {
+                break;
+            }
+        }
+
+        Self { digits, start }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.digits[self.start..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digests() {
+        // From 2015 day 4's examples
+        let hash = md5("abcdef", 609043);
+        assert!(to_hex(&hash).starts_with("000001dbbfa"));
+        assert_eq!(leading_zeroes(&hash), 5);
+        assert_eq!(nibble(&hash, 5), 1);
+        assert_eq!(nibble(&hash, 6), 0xd);
+
+        assert_eq!(Decimal::new(0).as_bytes(), b"0");
+        assert_eq!(Decimal::new(u64::MAX).as_bytes(), b"18446744073709551615");
+    }
+
+    #[test]
+    fn lowest() {
+        // The lowest match is found however the work is split up
+        let three_zeroes = |h: &Hash| leading_zeroes(h) >= 3;
+        let expected = search("abc", 0, 1, &three_zeroes);
+        for threads in [2, 3, 8] {
+            assert_eq!(search("abc", 0, threads, &three_zeroes), expected);
+        }
+        assert_eq!(search("abc", expected, 4, &three_zeroes), expected);
+    }
+
+    #[test]
+    #[ignore = "slow unless optimized"]
+    fn examples() {
+        // 2015 day 4's examples
+        let five_zeroes = |h: &Hash| leading_zeroes(h) >= 5;
+        assert_eq!(lowest_suffix("abcdef", 0, five_zeroes), 609043);
+        assert_eq!(search("pqrstuv", 0, 1, &five_zeroes), 1048970);
+    }
+
+    #[test]
+    fn successive() {
+        let three_zeroes = |h: &Hash| leading_zeroes(h) >= 3;
+        let found: Vec<_> = suffixes("abc", 0, three_zeroes).take(3).collect();
+        assert!(found.windows(2).all(|w| w[0].0 < w[1].0));
+        for (n, hash) in found {
+            assert_eq!(hash, md5("abc", n));
+            assert!(three_zeroes(&hash));
+        }
+    }
+
+    #[test]
+    #[ignore = "slow unless optimized"]
+    fn password() {
+        // 2016 day 5's example: the first characters of the door ID "abc"'s password
+        let password: String = suffixes("abc", 0, |h| leading_zeroes(h) >= 5)
+            .take(3)
+            .map(|(_, h)| format!("{:x}", nibble(&h, 5)))
+            .collect();
+        assert_eq!(password, "18f");
+    }
+
+    /// Compare searching with one thread to searching with every available thread; see the
+    /// [crate docs](crate) for how to run it.
+    #[test]
+    #[ignore = "benchmark"]
+    fn bench_threads() {
+        use std::time::Instant;
+
+        let six_zeroes = |h: &Hash| leading_zeroes(h) >= 6;
+
+        let start = Instant::now();
+        let single = search("abcdef", 0, 1, &six_zeroes);
+        let single_time = start.elapsed();
+
+        let start = Instant::now();
+        let parallel = lowest_suffix("abcdef", 0, six_zeroes);
+        let parallel_time = start.elapsed();
+
+        assert_eq!(single, parallel);
+        println!("one thread: {single_time:?}, all threads: {parallel_time:?}");
+    }
+}
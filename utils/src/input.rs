@@ -0,0 +1,124 @@
+//! Helpers to check that puzzle inputs are what we expect them to be, both when they're first
+//! downloaded and each time they're used.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Check for the obvious signs that a downloaded "input" is actually something else, such as an
+/// error page or a message from the Advent of Code site.
+pub fn validate(input: &str) -> Result<(), String> {
+    let start = input.trim_start().to_lowercase();
+
+    if start.is_empty() {
+        Err("Input is empty".into())
+    } else if start.starts_with("<!doctype") || start.starts_with("<html") {
+        Err("Input is an HTML page (are you logged in?)".into())
+    } else if start.starts_with("puzzle inputs differ by user") {
+        Err("Input is a login prompt; check your session cookie".into())
+    } else if start.starts_with("please don't repeatedly request this endpoint") {
+        Err("Input is not unlocked yet".into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Normalize an input so solvers needn't worry about how it was saved: convert any CRLF line
+/// endings to LF and make sure there's a trailing newline (as there is on every Advent of Code
+/// input).
+pub fn normalize(input: &str) -> String {
+    let mut input = input.replace("\r\n", "\n");
+    if !input.ends_with('\n') {
+        input.push('\n');
+    }
+
+    input
+}
+
+/// Compute a checksum of an input (64-bit FNV-1a). This only needs to notice accidental edits, and
+/// must be stable across Rust versions, so the standard library's hashers aren't suitable.
+pub fn checksum(input: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    input.bytes().fold(OFFSET_BASIS, |hash, b| {
+        (hash ^ u64::from(b)).wrapping_mul(PRIME)
+    })
+}
+
+/// Get the path of the file recording the checksum of the input at the given path.
+pub fn checksum_path<P: AsRef<Path>>(input_path: P) -> PathBuf {
+    let mut path = input_path.as_ref().as_os_str().to_owned();
+    path.push(".checksum");
+    path.into()
+}
+
+/// Record the checksum of an (already [normalized](normalize)) input.
+pub fn record_checksum<P: AsRef<Path>>(input_path: P, input: &str) -> io::Result<()> {
+    fs::write(
+        checksum_path(input_path),
+        format!("{:016x}\n", checksum(input)),
+    )
+}
+
+/// Check an (already [normalized](normalize)) input against its recorded checksum, recording one
+/// if there isn't one yet. Returns a description of the problem if the input has changed.
+pub fn verify_checksum<P: AsRef<Path>>(input_path: P, input: &str) -> io::Result<Option<String>> {
+    let input_path = input_path.as_ref();
+    let recorded = match fs::read_to_string(checksum_path(input_path)) {
+        Ok(recorded) => recorded,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            record_checksum(input_path, input)?;
+            return Ok(None);
+        }
+        Err(e) => return Err(e),
+    };
+
+    let actual = format!("{:016x}", checksum(input));
+    if recorded.trim() == actual {
+        Ok(None)
+    } else {
+        Ok(Some(format!(
+            "{} has changed since it was downloaded (checksum {actual}, expected {}); if this was intentional, delete {}",
+            input_path.display(),
+            recorded.trim(),
+            checksum_path(input_path).display()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_input() {
+        assert!(validate("1,2,3\n").is_ok());
+        assert!(validate("").is_err());
+        assert!(validate("<!DOCTYPE html>\n<html>").is_err());
+        assert!(validate(
+            "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn normalize_input() {
+        let cases = vec![
+            ("a\r\nb\r\n", "a\nb\n"),
+            ("a\nb", "a\nb\n"),
+            ("a\nb\n", "a\nb\n"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(normalize(input), expected);
+        }
+    }
+
+    #[test]
+    fn checksums() {
+        // Reference values from the FNV test suite
+        assert_eq!(checksum(""), 0xcbf29ce484222325);
+        assert_eq!(checksum("a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(checksum("foobar"), 0x85944171f73967e8);
+    }
+}
@@ -1,5 +1,22 @@
+//! Code shared by every year's solutions: the runner, input parsing, and the data structures &
+//! algorithms more than one puzzle needs.
+//!
+//! Benchmarks, and tests which are too slow without optimizations, are ignored; run them with
+//! `cargo test -p utils --release -- --ignored --nocapture`.
+
 mod build;
 mod day;
+pub mod exprgraph;
+pub mod geom;
+pub mod grid;
+pub mod hashsearch;
+pub mod input;
+pub mod math;
+pub mod parse;
+pub mod rects;
+pub mod render;
+pub mod search;
+pub mod viz;
 
 pub use build::*;
 pub use day::*;
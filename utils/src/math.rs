@@ -0,0 +1,394 @@
+//! Number theory and combinatorics that keep coming up: greatest common divisors, modular
+//! arithmetic, the Chinese remainder theorem, integer roots, extrapolating polynomials, and
+//! searching through combinations of values.
+
+use std::ops::{Div, RangeInclusive, Rem};
+
+use crate::geom::Coord;
+
+/// The greatest common divisor of two integers (which is never negative)
+pub fn gcd<T: Coord + Rem<Output = T>>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a, b);
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+
+    if a < T::ZERO {
+        T::ZERO - a
+    } else {
+        a
+    }
+}
+
+/// The least common multiple of two integers
+pub fn lcm<T: Coord + Rem<Output = T> + Div<Output = T>>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        return T::ZERO;
+    }
+
+    let lcm = a / gcd(a, b) * b;
+    if lcm < T::ZERO {
+        T::ZERO - lcm
+    } else {
+        lcm
+    }
+}
+
+/// The extended Euclidean algorithm: returns `(g, x, y)` such that `a*x + b*y = g = gcd(a, b)`.
+pub fn egcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `base` to the power of `exp`, modulo `m`
+pub fn mod_pow(base: u64, exp: u64, m: u64) -> u64 {
+    let m = u128::from(m);
+    let (mut base, mut exp) = (u128::from(base) % m, exp);
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+
+    result as u64
+}
+
+/// The inverse of `a` modulo `m` (in `0..m`), if there is one; i.e., if `a` and `m` are coprime.
+pub fn mod_inv(a: i64, m: i64) -> Option<i64> {
+    let (g, x, _) = egcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// Solve a system of congruences `x ≡ r (mod m)`, each given as `(r, m)`, with the Chinese
+/// remainder theorem. The moduli needn't be coprime. Returns `(x, m)` where `m` is the least
+/// common multiple of the moduli and `x` is in `0..m`, or `None` if there's no solution.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let (mut x, mut m) = (0_i128, 1_i128);
+
+    for &(r, n) in congruences {
+        let (r, n) = (i128::from(r), i128::from(n));
+
+        // Find k such that x + m*k ≡ r (mod n)
+        let (g, p, _) = egcd(i64::try_from(m % n).ok()?, i64::try_from(n).ok()?);
+        let g = i128::from(g);
+        if (r - x) % g != 0 {
+            return None;
+        }
+        let k = ((r - x) / g % (n / g) * i128::from(p)).rem_euclid(n / g);
+
+        x += m * k;
+        m *= n / g;
+        x = x.rem_euclid(m);
+    }
+
+    Some((i64::try_from(x).ok()?, i64::try_from(m).ok()?))
+}
+
+/// The integer square root of `n`; i.e., the largest `r` such that `r * r <= n`.
+pub fn isqrt(n: u64) -> u64 {
+    isqrt_u128(u128::from(n)) as u64
+}
+
+/// [isqrt] for numbers too big for a `u64` (e.g., products of two `i64`s).
+pub fn isqrt_u128(n: u128) -> u128 {
+    // Start from the floating-point estimate, and correct it for any rounding
+    let mut r = (n as f64).sqrt() as u128;
+    while r.checked_mul(r).is_none_or(|sq| sq > n) {
+        r -= 1;
+    }
+    while (r + 1).checked_mul(r + 1).is_some_and(|sq| sq <= n) {
+        r += 1;
+    }
+
+    r
+}
+
+/// The integers `x` for which `a*x² + b*x + c < 0`, where `a > 0`, if there are any. E.g.,
+/// for a boat race of length `t` with a record of `d`, the times to hold the button to win are
+/// `quadratic_below_zero(1, -t, d)`.
+///
+/// Panics if the quadratic's discriminant or the range of integers doesn't fit in an `i128` or
+/// an `i64` respectively, rather than giving a wrong answer.
+pub fn quadratic_below_zero(a: i64, b: i64, c: i64) -> Option<RangeInclusive<i64>> {
+    assert!(a > 0, "The quadratic must open upwards (a > 0)");
+    let (a, b, c) = (i128::from(a), i128::from(b), i128::from(c));
+    let f = |x: i128| a * x * x + b * x + c;
+
+    let discriminant = (4 * a)
+        .checked_mul(c)
+        .and_then(|ac| (b * b).checked_sub(ac))
+        .expect("The discriminant overflows an i128");
+    if discriminant <= 0 {
+        return None;
+    }
+
+    // The roots are (-b ± √disc) / 2a; start at the integers just inside them and adjust for the
+    // rounding of the square root.
+    let root = isqrt_u128(discriminant as u128) as i128;
+    let mut lo = (-b - root).div_euclid(2 * a);
+    let mut hi = (-b + root).div_euclid(2 * a) + 1;
+    while f(lo) >= 0 && lo <= hi {
+        lo += 1;
+    }
+    while f(lo - 1) < 0 {
+        lo -= 1;
+    }
+    while f(hi) >= 0 && hi >= lo {
+        hi -= 1;
+    }
+    while f(hi + 1) < 0 {
+        hi += 1;
+    }
+
+    if lo > hi {
+        return None;
+    }
+    let to_i64 = |x: i128| i64::try_from(x).expect("The integers don't fit in an i64");
+    Some(to_i64(lo)..=to_i64(hi))
+}
+
+/// Extrapolate the sequence `ys`, taken to be the values of the polynomial of least degree (the
+/// Lagrange polynomial) at `x = 0, 1, 2, ...`, to its value at `x`, which may be negative; e.g.,
+/// `x = ys.len()` is the next value of the sequence, and `x = -1` is the previous one.
+pub fn lagrange_extrapolate(ys: &[i64], x: i64) -> i64 {
+    // Work with Newton's form of the polynomial: the sum of each k-th difference of the sequence
+    // (at x = 0) times `x choose k`. Everything stays an integer.
+    let mut diffs: Vec<i128> = ys.iter().map(|&y| i128::from(y)).collect();
+    let x = i128::from(x);
+    let mut result = 0;
+    let mut binomial = 1; // x choose k
+
+    for k in 0..ys.len() {
+        result += binomial * diffs[0];
+
+        diffs = diffs.windows(2).map(|w| w[1] - w[0]).collect();
+        let k = k as i128;
+        binomial = binomial * (x - k) / (k + 1);
+    }
+
+    result as i64
+}
+
+/// Every combination of `k` of the items (in the order they're given), as an iterator of `Vec`s of
+/// references to them.
+pub fn combinations<T>(items: &[T], k: usize) -> impl Iterator<Item = Vec<&T>> {
+    let n = items.len();
+    let mut indices: Option<Vec<usize>> = (k <= n).then(|| (0..k).collect());
+
+    std::iter::from_fn(move || {
+        let current = indices.as_mut()?;
+        let combination = current.iter().map(|&i| &items[i]).collect();
+
+        // Advance to the next combination: bump the rightmost index which can be, then reset the
+        // ones after it.
+        match (0..k).rev().find(|&i| current[i] < n - k + i) {
+            Some(i) => {
+                current[i] += 1;
+                for j in i + 1..k {
+                    current[j] = current[j - 1] + 1;
+                }
+            }
+            None => indices = None,
+        }
+
+        Some(combination)
+    })
+}
+
+/// Every permutation of the items, in lexicographic order of their positions, as an iterator of
+/// `Vec`s of references to them.
+pub fn permutations<T>(items: &[T]) -> impl Iterator<Item = Vec<&T>> {
+    let mut indices: Option<Vec<usize>> = Some((0..items.len()).collect());
+
+    std::iter::from_fn(move || {
+        let current = indices.as_mut()?;
+        let permutation = current.iter().map(|&i| &items[i]).collect();
+
+        // Advance to the next permutation, as in C++'s `next_permutation`
+        match (1..current.len())
+            .rev()
+            .find(|&i| current[i - 1] < current[i])
+        {
+            Some(i) => {
+                let j = (i..current.len())
+                    .rev()
+                    .find(|&j| current[j] > current[i - 1])
+                    .unwrap();
+                current.swap(i - 1, j);
+                current[i..].reverse();
+            }
+            None => indices = None,
+        }
+
+        Some(permutation)
+    })
+}
+
+/// Find `k` values (at distinct positions) which sum to `target`, returning their indices in
+/// ascending order. Takes `O(n^(k-1))` time, rather than the `O(n^k)` of trying every
+/// combination.
+pub fn k_sum(values: &[i64], k: usize, target: i64) -> Option<Vec<usize>> {
+    let mut sorted: Vec<usize> = (0..values.len()).collect();
+    sorted.sort_by_key(|&i| values[i]);
+
+    let mut found = k_sum_sorted(values, &sorted, k, target)?;
+    found.sort();
+    Some(found)
+}
+
+/// [k_sum] over the `indices` of `values`, sorted by value
+fn k_sum_sorted(values: &[i64], indices: &[usize], k: usize, target: i64) -> Option<Vec<usize>> {
+    match k {
+        0 => (target == 0).then(Vec::new),
+        1 => indices
+            .iter()
+            .find(|&&i| values[i] == target)
+            .map(|&i| vec![i]),
+        2 => {
+            // Close in from both ends of the sorted values
+            let (mut lo, mut hi) = (0, indices.len().checked_sub(1)?);
+            while lo < hi {
+                let sum = values[indices[lo]] + values[indices[hi]];
+                match sum.cmp(&target) {
+                    std::cmp::Ordering::Less => lo += 1,
+                    std::cmp::Ordering::Greater => hi -= 1,
+                    std::cmp::Ordering::Equal => return Some(vec![indices[lo], indices[hi]]),
+                }
+            }
+            None
+        }
+        _ => (0..indices.len()).find_map(|first| {
+            let i = indices[first];
+            let mut rest = k_sum_sorted(values, &indices[first + 1..], k - 1, target - values[i])?;
+            rest.push(i);
+            Some(rest)
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn divisors() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(0u32, 5), 5);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(-4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+
+        let (g, x, y) = egcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+    }
+
+    #[test]
+    fn modular() {
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(mod_pow(u64::MAX, u64::MAX, u64::MAX - 1), 1);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+
+        assert_eq!(mod_inv(3, 11), Some(4));
+        assert_eq!(mod_inv(-3, 11), Some(7));
+        assert_eq!(mod_inv(6, 9), None);
+
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+
+        // 2020 day 13's example: buses 7,13,x,x,59,x,31,19 leaving at offsets 0,1,4,6,7
+        let buses = [(7, 0), (13, 1), (59, 4), (31, 6), (19, 7)];
+        let congruences: Vec<_> = buses.iter().map(|&(b, i)| (-i, b)).collect();
+        assert_eq!(crt(&congruences).unwrap().0, 1068781);
+    }
+
+    #[test]
+    fn roots() {
+        for n in [0, 1, 2, 3, 4, 15, 16, 17, 1 << 52, (1 << 52) + 1, u64::MAX] {
+            let r = isqrt(n);
+            assert!(r * r <= n, "{n}");
+            assert!((r + 1).checked_mul(r + 1).is_none_or(|sq| sq > n), "{n}");
+        }
+        for n in [
+            u128::from(u64::MAX) + 1,
+            1 << 100,
+            (1 << 100) - 1,
+            u128::MAX,
+        ] {
+            let r = isqrt_u128(n);
+            assert!(r * r <= n, "{n}");
+            assert!((r + 1).checked_mul(r + 1).is_none_or(|sq| sq > n), "{n}");
+        }
+
+        // 2023 day 6's example races: (time, record) and the ways to win
+        assert_eq!(quadratic_below_zero(1, -7, 9), Some(2..=5));
+        assert_eq!(quadratic_below_zero(1, -15, 40), Some(4..=11));
+        assert_eq!(quadratic_below_zero(1, -30, 200), Some(11..=19));
+
+        assert_eq!(quadratic_below_zero(1, 0, -4), Some(-1..=1));
+        assert_eq!(quadratic_below_zero(1, 0, 0), None);
+        assert_eq!(quadratic_below_zero(2, 0, -1), Some(0..=0));
+
+        // A discriminant too big for a u64
+        let t = 1 << 40;
+        assert_eq!(quadratic_below_zero(1, -t, 0), Some(1..=t - 1));
+    }
+
+    #[test]
+    fn extrapolate() {
+        // 2023 day 9's example histories
+        assert_eq!(lagrange_extrapolate(&[0, 3, 6, 9, 12, 15], 6), 18);
+        assert_eq!(lagrange_extrapolate(&[1, 3, 6, 10, 15, 21], 6), 28);
+        assert_eq!(lagrange_extrapolate(&[10, 13, 16, 21, 30, 45], 6), 68);
+        assert_eq!(lagrange_extrapolate(&[10, 13, 16, 21, 30, 45], -1), 5);
+        assert_eq!(lagrange_extrapolate(&[1, 4, 9], 10), 121);
+        assert_eq!(lagrange_extrapolate(&[], 10), 0);
+    }
+
+    #[test]
+    fn combinatorics() {
+        let items = [1, 2, 3, 4];
+        let combos: Vec<Vec<&i32>> = combinations(&items, 2).collect();
+        assert_eq!(combos.len(), 6);
+        assert_eq!(combos[0], vec![&1, &2]);
+        assert_eq!(combos[5], vec![&3, &4]);
+        assert_eq!(combinations(&items, 0).count(), 1);
+        assert_eq!(combinations(&items, 5).count(), 0);
+
+        let perms: Vec<Vec<&i32>> = permutations(&items[..3]).collect();
+        assert_eq!(perms.len(), 6);
+        assert_eq!(perms[1], vec![&1, &3, &2]);
+        assert_eq!(perms[5], vec![&3, &2, &1]);
+        assert_eq!(permutations::<i32>(&[]).count(), 1);
+    }
+
+    #[test]
+    fn sums() {
+        // 2020 day 1's example expense report
+        let values = [1721, 979, 366, 299, 675, 1456];
+        assert_eq!(k_sum(&values, 2, 2020), Some(vec![0, 3]));
+        assert_eq!(k_sum(&values, 3, 2020), Some(vec![1, 2, 4]));
+        assert_eq!(k_sum(&values, 2, 1), None);
+        assert_eq!(k_sum(&[1010], 2, 2020), None);
+        assert_eq!(k_sum(&values, 1, 366), Some(vec![2]));
+    }
+}
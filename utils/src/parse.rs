@@ -0,0 +1,475 @@
+//! Helpers for the parsing that nearly every puzzle needs, plus a small [Cursor] for picking apart
+//! lines with a fixed format.
+//!
+//! Errors are [ParseError]s, which record the line (and column) where parsing failed. They convert
+//! into a `Box<dyn Error>` with `?`, so the runner reports where an input went wrong rather than
+//! just, e.g., "invalid digit found in string".
+
+use std::error::Error;
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+use crate::grid::Grid;
+
+/// An error parsing (part of) a puzzle input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The line the error occurred on, counting from 1
+    pub line: Option<usize>,
+
+    /// The column the error occurred at, counting from 1
+    pub column: Option<usize>,
+
+    pub msg: String,
+}
+
+impl ParseError {
+    pub fn new(msg: impl fmt::Display) -> Self {
+        Self {
+            line: None,
+            column: None,
+            msg: msg.to_string(),
+        }
+    }
+
+    /// Record the line the error occurred on, if it isn't already known
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line.get_or_insert(line);
+        self
+    }
+
+    /// Record the column the error occurred at, if it isn't already known
+    pub fn at_column(mut self, column: usize) -> Self {
+        self.column.get_or_insert(column);
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "line {line}, column {column}: {}", self.msg),
+            (Some(line), None) => write!(f, "line {line}: {}", self.msg),
+            (None, Some(column)) => write!(f, "column {column}: {}", self.msg),
+            (None, None) => write!(f, "{}", self.msg),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl From<String> for ParseError {
+    fn from(msg: String) -> Self {
+        Self::new(msg)
+    }
+}
+
+impl From<&str> for ParseError {
+    fn from(msg: &str) -> Self {
+        Self::new(msg)
+    }
+}
+
+impl From<ParseIntError> for ParseError {
+    fn from(e: ParseIntError) -> Self {
+        Self::new(e)
+    }
+}
+
+impl From<Box<dyn Error>> for ParseError {
+    fn from(e: Box<dyn Error>) -> Self {
+        // Keep the position of errors which are already `ParseError`s
+        match e.downcast::<Self>() {
+            Ok(e) => *e,
+            Err(e) => Self::new(e),
+        }
+    }
+}
+
+/// Parse each (non-blank) line of the input with `f`, recording the line number of any error.
+pub fn lines<'a, T, E, F>(input: &'a str, mut f: F) -> Result<Vec<T>, ParseError>
+where
+    F: FnMut(&'a str) -> Result<T, E>,
+    E: Into<ParseError>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i, l.trim_end_matches('\r')))
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| f(l).map_err(|e| e.into().at_line(i + 1)))
+        .collect()
+}
+
+/// Extract every integer from a string, ignoring anything between them. A `-` directly before a
+/// number is a minus sign unless it follows a digit (so the range `2-4` is `[2, 4]`).
+pub fn ints<T: FromStr>(s: &str) -> Result<Vec<T>, ParseError> {
+    let bytes = s.as_bytes();
+    let mut values = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let negative = bytes[i] == b'-'
+            && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+            && (i == 0 || !bytes[i - 1].is_ascii_digit());
+        if !negative && !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        i += 1;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+
+        let token = &s[start..i];
+        let value = token.parse().map_err(|_| {
+            ParseError::new(format!("Invalid {}: {token}", std::any::type_name::<T>()))
+                .at_column(start + 1)
+        })?;
+        values.push(value);
+    }
+
+    Ok(values)
+}
+
+/// Split the input into blocks separated by blank lines (which may contain whitespace, or end in
+/// `\r\n`). Leading and trailing blank lines are ignored.
+pub fn blocks(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+
+    std::iter::from_fn(move || {
+        // Skip any blank lines before the block
+        loop {
+            let (line, after) = rest.split_once('\n').unwrap_or((rest, ""));
+            if rest.is_empty() || !line.trim().is_empty() {
+                break;
+            }
+            rest = after;
+        }
+        if rest.is_empty() {
+            return None;
+        }
+
+        // The block runs until the next blank line (or the end of the input)
+        let mut end = 0;
+        let mut next = rest.len();
+        for line in rest.split_inclusive('\n') {
+            if line.trim().is_empty() {
+                next = end + line.len();
+                break;
+            }
+            end += line.len();
+        }
+
+        let block = rest[..end].trim_end_matches(['\r', '\n']);
+        rest = &rest[next.min(rest.len())..];
+        Some(block)
+    })
+}
+
+/// Parse a map with one character per cell, as [`Grid<char>`](Grid). Unlike parsing the grid
+/// directly, a row of the wrong length is reported with its line number.
+///
+/// Whitespace is part of the map, and the map ends at the first blank line; anything after that
+/// is an error (use [blocks] first to parse several maps, or a map followed by other sections).
+pub fn grid_chars(input: &str) -> Result<Grid<char>, ParseError> {
+    let mut rows: Vec<Vec<char>> = Vec::new();
+    let mut ended = false;
+
+    for (i, line) in input.lines().enumerate() {
+        if line.is_empty() {
+            ended = true;
+            continue;
+        }
+        if ended {
+            return Err(
+                ParseError::new("Unexpected text after a blank line ending the map").at_line(i + 1),
+            );
+        }
+
+        let row: Vec<char> = line.chars().collect();
+        if let Some(first) = rows.first() {
+            if row.len() != first.len() {
+                return Err(ParseError::new(format!(
+                    "Expected {} columns, found {}",
+                    first.len(),
+                    row.len()
+                ))
+                .at_line(i + 1));
+            }
+        }
+        rows.push(row);
+    }
+
+    Ok(Grid::from_rows(rows)?)
+}
+
+/// Split a string into `key`/`value` pairs; e.g., `"a: 1, b: 2"` with separators `", "` and
+/// `": "` is `[("a", "1"), ("b", "2")]`. Keys and values are trimmed.
+pub fn kv_pairs<'a>(
+    s: &'a str,
+    pair_sep: &str,
+    kv_sep: &str,
+) -> Result<Vec<(&'a str, &'a str)>, ParseError> {
+    s.split(pair_sep)
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            pair.split_once(kv_sep)
+                .map(|(k, v)| (k.trim(), v.trim()))
+                .ok_or_else(|| ParseError::new(format!("No '{kv_sep}' in: '{pair}'")))
+        })
+        .collect()
+}
+
+/// Reads a string from left to right, for parsing lines with a fixed format; e.g., `move 1 from 2
+/// to 3` is
+///
+/// ```
+/// # use utils::parse::Cursor;
+/// let mut c = Cursor::new("move 1 from 2 to 3");
+/// c.literal("move")?;
+/// let count: usize = c.int()?;
+/// c.literal("from")?;
+/// let from: usize = c.int()?;
+/// c.literal("to")?;
+/// let to: usize = c.int()?;
+/// c.end()?;
+/// # assert_eq!((count, from, to), (1, 2, 3));
+/// # Ok::<(), utils::parse::ParseError>(())
+/// ```
+///
+/// Whitespace before each item is skipped. Errors record the column they occurred at.
+#[derive(Debug, Clone, Copy)]
+pub struct Cursor<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    /// The part of the input not yet read
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rest().trim().is_empty()
+    }
+
+    /// An error at the current position
+    pub fn error(&self, msg: impl fmt::Display) -> ParseError {
+        ParseError::new(msg).at_column(self.pos + 1)
+    }
+
+    fn skip_ws(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Take the next `len` bytes of input
+    fn take(&mut self, len: usize) -> &'a str {
+        let taken = &self.rest()[..len];
+        self.pos += len;
+        taken
+    }
+
+    /// Read the given text
+    pub fn literal(&mut self, lit: &str) -> Result<(), ParseError> {
+        self.skip_ws();
+        if !self.rest().starts_with(lit) {
+            return Err(self.error(format!("Expected '{lit}', found '{}'", self.rest())));
+        }
+
+        self.take(lit.len());
+        Ok(())
+    }
+
+    /// Read the given text, if it's next; returns whether it was
+    pub fn eat(&mut self, lit: &str) -> bool {
+        self.literal(lit).is_ok()
+    }
+
+    /// Read an integer (with an optional sign)
+    pub fn int<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        self.skip_ws();
+        let rest = self.rest();
+        let sign = usize::from(rest.starts_with(['-', '+']));
+        let digits = rest[sign..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len() - sign);
+        if digits == 0 {
+            return Err(self.error(format!("Expected a number, found '{rest}'")));
+        }
+
+        let error = self.error(format!("Invalid {}", std::any::type_name::<T>()));
+        self.take(sign + digits).parse().map_err(|_| error)
+    }
+
+    /// Read a word; i.e., a run of letters, digits and underscores
+    pub fn word(&mut self) -> Result<&'a str, ParseError> {
+        self.skip_ws();
+        let len = self
+            .rest()
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(self.rest().len());
+        if len == 0 {
+            return Err(self.error(format!("Expected a word, found '{}'", self.rest())));
+        }
+
+        Ok(self.take(len))
+    }
+
+    /// Read everything up to (but not including) the delimiter, which is then skipped over.
+    pub fn until(&mut self, delim: &str) -> Result<&'a str, ParseError> {
+        self.skip_ws();
+        let len = self
+            .rest()
+            .find(delim)
+            .ok_or_else(|| self.error(format!("Expected '{delim}'")))?;
+
+        let taken = self.take(len);
+        self.take(delim.len());
+        Ok(taken)
+    }
+
+    /// Try to read something with `f`, leaving the cursor where it was if that fails
+    pub fn opt<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, ParseError>) -> Option<T> {
+        let mut attempt = *self;
+        let value = f(&mut attempt).ok()?;
+        *self = attempt;
+        Some(value)
+    }
+
+    /// Read one or more items with `f`, separated by `sep`
+    pub fn sep_by<T>(
+        &mut self,
+        sep: &str,
+        mut f: impl FnMut(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        let mut items = vec![f(self)?];
+        while self.opt(|c| c.literal(sep)).is_some() {
+            items.push(f(self)?);
+        }
+
+        Ok(items)
+    }
+
+    /// Check that there's nothing left but whitespace
+    pub fn end(&mut self) -> Result<(), ParseError> {
+        self.skip_ws();
+        match self.is_empty() {
+            true => Ok(()),
+            false => Err(self.error(format!("Unexpected '{}'", self.rest()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_ints() {
+        assert_eq!(
+            ints::<i32>("x=-12, y=3 (7-9) --4").unwrap(),
+            vec![-12, 3, 7, 9, -4]
+        );
+        assert_eq!(ints::<u8>("2-4,6-8").unwrap(), vec![2, 4, 6, 8]);
+        assert_eq!(ints::<u8>("none here").unwrap(), vec![]);
+
+        let err = ints::<u8>("1 2 300").unwrap_err();
+        assert_eq!(err.to_string(), "column 5: Invalid u8: 300");
+    }
+
+    #[test]
+    fn split_blocks() {
+        let input = "\na\nb\n\n  \nc\r\n\r\nd\n\n";
+        assert_eq!(blocks(input).collect::<Vec<_>>(), vec!["a\nb", "c", "d"]);
+        assert_eq!(blocks("").count(), 0);
+        assert_eq!(blocks("a").collect::<Vec<_>>(), vec!["a"]);
+    }
+
+    #[test]
+    fn line_numbers() {
+        let input = "1 2\n\n3 4\r\n5 x\n";
+        let err = lines(input, |l| {
+            l.split_whitespace()
+                .map(|n| n.parse::<u32>())
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "line 4: invalid digit found in string");
+
+        let parsed = lines("a\r\nb", Ok::<_, ParseError>).unwrap();
+        assert_eq!(parsed, vec!["a", "b"]);
+
+        let err = grid_chars("ab\ncd\nefg").unwrap_err();
+        assert_eq!(err.to_string(), "line 3: Expected 2 columns, found 3");
+        let grid = grid_chars("ab\r\ncd\r\n\n").unwrap();
+        assert_eq!(grid.to_string(), "ab\ncd");
+
+        // Spaces are cells, and a blank line ends the map
+        let grid = grid_chars(" a\nb \n").unwrap();
+        assert_eq!((grid.rows(), grid.cols(), grid[(1, 1)]), (2, 2, ' '));
+        let err = grid_chars("ab\ncd\n\nef\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 4: Unexpected text after a blank line ending the map"
+        );
+    }
+
+    #[test]
+    fn pairs() {
+        assert_eq!(
+            kv_pairs("a: 1, b:2,", ",", ":").unwrap(),
+            vec![("a", "1"), ("b", "2")]
+        );
+        assert!(kv_pairs("a: 1, b", ",", ":").is_err());
+    }
+
+    #[test]
+    fn cursor() {
+        let mut c = Cursor::new("Game 12: 3 blue, -4 red; 1 green");
+        c.literal("Game").unwrap();
+        assert_eq!(c.int::<u32>().unwrap(), 12);
+        c.literal(":").unwrap();
+        let draws = c
+            .sep_by(";", |c| c.sep_by(",", |c| Ok((c.int::<i32>()?, c.word()?))))
+            .unwrap();
+        assert_eq!(
+            draws,
+            vec![vec![(3, "blue"), (-4, "red")], vec![(1, "green")]]
+        );
+        c.end().unwrap();
+
+        let mut c = Cursor::new("move x");
+        assert!(!c.eat("from"));
+        assert!(c.eat("move"));
+        assert_eq!(
+            c.int::<u8>().unwrap_err().to_string(),
+            "column 6: Expected a number, found 'x'"
+        );
+        assert_eq!(c.opt(|c| c.int::<u8>()), None);
+        assert_eq!(
+            c.until("!").unwrap_err().to_string(),
+            "column 6: Expected '!'"
+        );
+        assert_eq!(c.word().unwrap(), "x");
+        c.end().unwrap();
+
+        let mut c = Cursor::new("a -> b  junk");
+        assert_eq!(c.until("->").unwrap(), "a ");
+        assert_eq!(c.word().unwrap(), "b");
+        assert_eq!(
+            c.end().unwrap_err().to_string(),
+            "column 9: Unexpected 'junk'"
+        );
+    }
+}
@@ -0,0 +1,485 @@
+//! Rectangles of cells, and maps from (large) areas of cells to values which are updated a
+//! rectangle at a time, as in 2015 day 6's grid of lights.
+//!
+//! A [RectMap] compresses its area into bands of rows which have the same values, with each band
+//! split into runs of columns which have the same value. Updating a rectangle only touches the
+//! runs it overlaps, however many cells they cover, and neighbouring bands & runs which end up
+//! with the same values are merged again. Like a [Grid], cells are addressed by `(row, col)` and
+//! rectangles by their (inclusive) corners.
+
+use std::cmp::{max, min};
+use std::ops::RangeInclusive;
+
+use crate::grid::{Grid, Point};
+
+/// A rectangle of cells, from its top left to its bottom right corner (inclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub top_left: Point,
+    pub bottom_right: Point,
+}
+
+impl Rect {
+    /// The rectangle with the given (inclusive) corners, in any order
+    pub fn new(a: impl Into<Point>, b: impl Into<Point>) -> Self {
+        let (a, b) = (a.into(), b.into());
+        Self {
+            top_left: Point::new(min(a.row, b.row), min(a.col, b.col)),
+            bottom_right: Point::new(max(a.row, b.row), max(a.col, b.col)),
+        }
+    }
+
+    /// The rectangle covering a `rows` by `cols` grid
+    pub fn of_size(rows: usize, cols: usize) -> Self {
+        assert!(rows > 0 && cols > 0, "Rectangles must contain a cell");
+        Self::new((0, 0), (rows - 1, cols - 1))
+    }
+
+    pub fn rows(&self) -> RangeInclusive<usize> {
+        self.top_left.row..=self.bottom_right.row
+    }
+
+    pub fn cols(&self) -> RangeInclusive<usize> {
+        self.top_left.col..=self.bottom_right.col
+    }
+
+    pub fn height(&self) -> usize {
+        self.bottom_right.row - self.top_left.row + 1
+    }
+
+    pub fn width(&self) -> usize {
+        self.bottom_right.col - self.top_left.col + 1
+    }
+
+    /// The number of cells in the rectangle
+    pub fn area(&self) -> usize {
+        self.height() * self.width()
+    }
+
+    pub fn contains(&self, p: impl Into<Point>) -> bool {
+        let p = p.into();
+        self.rows().contains(&p.row) && self.cols().contains(&p.col)
+    }
+
+    /// The cells in both this rectangle and the other, if there are any
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let top_left = Point::new(
+            max(self.top_left.row, other.top_left.row),
+            max(self.top_left.col, other.top_left.col),
+        );
+        let bottom_right = Point::new(
+            min(self.bottom_right.row, other.bottom_right.row),
+            min(self.bottom_right.col, other.bottom_right.col),
+        );
+
+        (top_left.row <= bottom_right.row && top_left.col <= bottom_right.col).then_some(Rect {
+            top_left,
+            bottom_right,
+        })
+    }
+
+    /// The cells in this rectangle but not the other, as (at most 4) disjoint rectangles: the
+    /// full-width strips above & below the other rectangle, then the pieces to its left & right.
+    pub fn subtract(&self, other: &Rect) -> Vec<Rect> {
+        let Some(overlap) = self.intersection(other) else {
+            return vec![*self];
+        };
+
+        let (top, bottom) = (self.top_left.row, self.bottom_right.row);
+        let (left, right) = (self.top_left.col, self.bottom_right.col);
+        let mut pieces = Vec::with_capacity(4);
+        if overlap.top_left.row > top {
+            pieces.push(Rect::new((top, left), (overlap.top_left.row - 1, right)));
+        }
+        if overlap.bottom_right.row < bottom {
+            pieces.push(Rect::new(
+                (overlap.bottom_right.row + 1, left),
+                (bottom, right),
+            ));
+        }
+        if overlap.top_left.col > left {
+            pieces.push(Rect::new(
+                (overlap.top_left.row, left),
+                (overlap.bottom_right.row, overlap.top_left.col - 1),
+            ));
+        }
+        if overlap.bottom_right.col < right {
+            pieces.push(Rect::new(
+                (overlap.top_left.row, overlap.bottom_right.col + 1),
+                (overlap.bottom_right.row, right),
+            ));
+        }
+
+        pieces
+    }
+}
+
+/// A value for every cell in a (bounded) area, stored as bands of rows which share their values,
+/// each made up of runs of columns which share a value
+#[derive(Debug, Clone)]
+pub struct RectMap<T> {
+    bounds: Rect,
+
+    /// The first row of each band, and its runs: the first column of each, and its value. Each
+    /// band (or run) continues up to the start of the next one, or the edge of the bounds.
+    bands: Vec<(usize, Vec<(usize, T)>)>,
+}
+
+impl<T: Clone + PartialEq> RectMap<T> {
+    /// Create a map with every cell in `bounds` set to `value`
+    pub fn new(bounds: Rect, value: T) -> Self {
+        Self {
+            bounds,
+            bands: vec![(bounds.top_left.row, vec![(bounds.top_left.col, value)])],
+        }
+    }
+
+    /// The area this map covers
+    pub fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    /// The number of rectangles the area is currently split into
+    pub fn num_rects(&self) -> usize {
+        self.bands.iter().map(|(_, runs)| runs.len()).sum()
+    }
+
+    /// The value of the given cell, if it's in the map's bounds
+    pub fn get(&self, p: impl Into<Point>) -> Option<&T> {
+        let p = p.into();
+        if !self.bounds.contains(p) {
+            return None;
+        }
+
+        let (_, runs) = &self.bands[self.bands.partition_point(|&(row, _)| row <= p.row) - 1];
+        let (_, value) = &runs[runs.partition_point(|&(col, _)| col <= p.col) - 1];
+        Some(value)
+    }
+
+    /// The rectangles the area is split into, and their values
+    pub fn iter(&self) -> impl Iterator<Item = (Rect, &T)> {
+        self.bands
+            .iter()
+            .enumerate()
+            .flat_map(move |(i, (top, runs))| {
+                let bottom = self
+                    .bands
+                    .get(i + 1)
+                    .map_or(self.bounds.bottom_right.row, |b| b.0 - 1);
+                runs.iter().enumerate().map(move |(j, (left, value))| {
+                    let right = runs
+                        .get(j + 1)
+                        .map_or(self.bounds.bottom_right.col, |r| r.0 - 1);
+                    (Rect::new((*top, *left), (bottom, right)), value)
+                })
+            })
+    }
+
+    /// Update the value of every cell in `region` (clipped to the map's bounds) with `f`
+    pub fn update(&mut self, region: Rect, mut f: impl FnMut(&mut T)) {
+        let Some(region) = region.intersection(&self.bounds) else {
+            return;
+        };
+
+        let first = split_at(&mut self.bands, region.top_left.row);
+        let last = if region.bottom_right.row == self.bounds.bottom_right.row {
+            self.bands.len()
+        } else {
+            split_at(&mut self.bands, region.bottom_right.row + 1)
+        };
+
+        for (_, runs) in &mut self.bands[first..last] {
+            let start = split_at(runs, region.top_left.col);
+            let end = if region.bottom_right.col == self.bounds.bottom_right.col {
+                runs.len()
+            } else {
+                split_at(runs, region.bottom_right.col + 1)
+            };
+
+            runs[start..end].iter_mut().for_each(|(_, v)| f(v));
+            merge_equal(runs, start.saturating_sub(1)..=end);
+        }
+
+        merge_equal(&mut self.bands, first.saturating_sub(1)..=last);
+    }
+
+    /// The total of `f(value) * area` over the map; e.g., the number of cells with a value
+    pub fn sum_by(&self, mut f: impl FnMut(&T) -> usize) -> usize {
+        self.iter().map(|(r, v)| f(v) * r.area()).sum()
+    }
+
+    /// The value of every cell in the map's bounds, as a grid whose top left is the top left of
+    /// the bounds
+    pub fn to_grid(&self) -> Grid<T> {
+        let (top, left) = (self.bounds.top_left.row, self.bounds.top_left.col);
+        let mut grid = Grid::new(
+            self.bounds.height(),
+            self.bounds.width(),
+            self.bands[0].1[0].1.clone(),
+        );
+        for (r, v) in self.iter() {
+            let top_left = (r.top_left.row - top, r.top_left.col - left);
+            let bottom_right = (r.bottom_right.row - top, r.bottom_right.col - left);
+            grid.region_mut(top_left, bottom_right)
+                .for_each(|(_, cell)| *cell = v.clone());
+        }
+
+        grid
+    }
+}
+
+/// Split the band (or run) containing `start` so that one starts there, and return its index.
+/// `start` must be within the bounds.
+fn split_at<V: Clone>(spans: &mut Vec<(usize, V)>, start: usize) -> usize {
+    let i = spans.partition_point(|&(s, _)| s <= start) - 1;
+    if spans[i].0 == start {
+        return i;
+    }
+
+    let value = spans[i].1.clone();
+    spans.insert(i + 1, (start, value));
+    i + 1
+}
+
+/// Merge the neighbouring bands (or runs) with equal values whose indices are in `range`
+fn merge_equal<V: PartialEq>(spans: &mut Vec<(usize, V)>, range: RangeInclusive<usize>) {
+    let mut i = min(*range.end(), spans.len() - 1);
+    while i > *range.start() {
+        if spans[i].1 == spans[i - 1].1 {
+            spans.remove(i);
+        }
+        i -= 1;
+    }
+}
+
+/// A set of cells in a (bounded) area, which cells are added to, removed from, or toggled in a
+/// rectangle at a time; see [RectMap].
+#[derive(Debug, Clone)]
+pub struct RectSet(RectMap<bool>);
+
+impl RectSet {
+    /// Create an empty set of cells within `bounds`
+    pub fn new(bounds: Rect) -> Self {
+        Self(RectMap::new(bounds, false))
+    }
+
+    /// Add every cell in the rectangle to the set
+    pub fn insert(&mut self, rect: Rect) {
+        self.0.update(rect, |v| *v = true);
+    }
+
+    /// Remove every cell in the rectangle from the set
+    pub fn remove(&mut self, rect: Rect) {
+        self.0.update(rect, |v| *v = false);
+    }
+
+    /// Add the cells in the rectangle which aren't in the set, and remove those which are
+    pub fn toggle(&mut self, rect: Rect) {
+        self.0.update(rect, |v| *v = !*v);
+    }
+
+    pub fn contains(&self, p: impl Into<Point>) -> bool {
+        self.0.get(p).copied().unwrap_or(false)
+    }
+
+    /// The number of cells in the set
+    pub fn area(&self) -> usize {
+        self.0.sum_by(|&v| v as usize)
+    }
+
+    /// The disjoint rectangles making up the set
+    pub fn rects(&self) -> impl Iterator<Item = Rect> + '_ {
+        self.0.iter().filter(|(_, &v)| v).map(|(r, _)| r)
+    }
+
+    /// Whether each cell in the set's bounds is in the set, as a grid whose top left is the top
+    /// left of the bounds
+    pub fn to_grid(&self) -> Grid<bool> {
+        self.0.to_grid()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rects() {
+        let r = Rect::new((5, 4), (2, 1));
+        assert_eq!(r.top_left, Point::new(2, 1));
+        assert_eq!((r.height(), r.width(), r.area()), (4, 4, 16));
+        assert!(r.contains((5, 1)));
+        assert!(!r.contains((6, 1)));
+
+        let other = Rect::new((3, 3), (9, 9));
+        assert_eq!(r.intersection(&other), Some(Rect::new((3, 3), (5, 4))));
+        assert_eq!(r.intersection(&Rect::new((6, 0), (9, 9))), None);
+
+        let pieces = r.subtract(&other);
+        assert_eq!(pieces.iter().map(Rect::area).sum::<usize>(), 16 - 6);
+        for (i, a) in pieces.iter().enumerate() {
+            assert_eq!(a.intersection(&other), None);
+            for b in &pieces[i + 1..] {
+                assert_eq!(a.intersection(b), None);
+            }
+        }
+        assert_eq!(r.subtract(&r), vec![]);
+        assert_eq!(r.subtract(&Rect::new((0, 0), (0, 0))), vec![r]);
+    }
+
+    #[test]
+    fn rect_map() {
+        let mut map = RectMap::new(Rect::of_size(10, 10), 0);
+        map.update(Rect::new((0, 0), (4, 9)), |v| *v += 1);
+        map.update(Rect::new((3, 3), (6, 6)), |v| *v += 2);
+        map.update(Rect::new((8, 8), (20, 20)), |v| *v += 5); // clipped to the bounds
+
+        assert_eq!(map.get((0, 0)), Some(&1));
+        assert_eq!(map.get((4, 4)), Some(&3));
+        assert_eq!(map.get((6, 6)), Some(&2));
+        assert_eq!(map.get((9, 9)), Some(&5));
+        assert_eq!(map.get((10, 10)), None);
+        assert_eq!(map.sum_by(|&v| v), 42 + 3 * 8 + 2 * 8 + 5 * 4);
+
+        // The map agrees with a grid updated cell by cell
+        let grid = map.to_grid();
+        for (p, v) in grid.enumerate() {
+            assert_eq!(map.get(p), Some(v), "{p}");
+        }
+    }
+
+    #[test]
+    fn uniform_updates() {
+        let mut map = RectMap::new(Rect::of_size(3, 5), 0);
+        for col in 0..5 {
+            map.update(Rect::new((1, col), (2, col)), |v| *v += col);
+        }
+        assert!(map.num_rects() > 5);
+
+        // Setting a region to one value leaves it as a single rectangle, however it was split
+        map.update(Rect::new((1, 0), (2, 4)), |v| *v = 7);
+        assert_eq!(map.num_rects(), 2);
+        assert_eq!(map.sum_by(|&v| v), 7 * 10);
+
+        // Neighbours which end up with the same value are merged, however they got it
+        map.update(Rect::new((0, 0), (0, 2)), |v| *v += 7);
+        map.update(Rect::new((0, 3), (0, 4)), |v| *v += 7);
+        assert_eq!(map.num_rects(), 1);
+        assert_eq!(map.iter().next(), Some((Rect::of_size(3, 5), &7)));
+    }
+
+    #[test]
+    fn matches_grid() {
+        // Pseudo-random updates, checked against a grid updated cell by cell
+        let mut seed: u64 = 6;
+        let mut next = move |n: usize| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as usize % n
+        };
+
+        let bounds = Rect::new((2, 3), (21, 17));
+        let mut map = RectMap::new(bounds, 0);
+        let mut grid = Grid::new(20, 15, 0);
+        for i in 0..200 {
+            let r = Rect::new((next(25), next(25)), (next(25), next(25)));
+            let update = |v: &mut usize| match i % 3 {
+                0 => *v += 1,
+                1 => *v = v.saturating_sub(1),
+                _ => *v = 2,
+            };
+
+            map.update(r, update);
+            if let Some(r) = r.intersection(&bounds) {
+                grid.region_mut(
+                    (r.top_left.row - 2, r.top_left.col - 3),
+                    (r.bottom_right.row - 2, r.bottom_right.col - 3),
+                )
+                .for_each(|(_, v)| update(v));
+            }
+            assert_eq!(map.to_grid(), grid, "after update {i}");
+        }
+
+        // The rectangles exactly cover the bounds
+        assert_eq!(
+            map.iter().map(|(r, _)| r.area()).sum::<usize>(),
+            bounds.area()
+        );
+        assert_eq!(map.get((2, 2)), None);
+        assert_eq!(map.get((21, 17)), grid.get((19, 14)));
+    }
+
+    #[test]
+    fn rect_set() {
+        // From 2015 day 6's examples
+        let mut set = RectSet::new(Rect::of_size(1000, 1000));
+        set.insert(Rect::new((0, 0), (999, 999)));
+        assert_eq!(set.area(), 1_000_000);
+        set.toggle(Rect::new((0, 0), (999, 0)));
+        assert_eq!(set.area(), 999_000);
+        set.remove(Rect::new((499, 499), (500, 500)));
+        assert_eq!(set.area(), 998_996);
+        assert!(!set.contains((500, 500)));
+        assert!(set.contains((501, 500)));
+        assert_eq!(set.rects().map(|r| r.area()).sum::<usize>(), 998_996);
+    }
+
+    /// Compare updating a map with updating every cell of a grid, for instructions like 2015 day
+    /// 6's; see the [crate docs](crate) for how to run it.
+    #[test]
+    #[ignore = "benchmark"]
+    fn bench_grid() {
+        use std::time::Instant;
+
+        // Pseudo-random rectangles, from a linear congruential generator
+        let mut seed: u64 = 2015;
+        let mut next = move || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as usize % 1000
+        };
+        let rects: Vec<Rect> = (0..300)
+            .map(|_| Rect::new((next(), next()), (next(), next())))
+            .collect();
+
+        let start = Instant::now();
+        let mut grid = Grid::new(1000, 1000, 0_usize);
+        for (i, r) in rects.iter().enumerate() {
+            grid.region_mut(r.top_left, r.bottom_right)
+                .for_each(|(_, v)| *v += i % 3);
+        }
+        let grid_time = start.elapsed();
+
+        let start = Instant::now();
+        let mut map = RectMap::new(Rect::of_size(1000, 1000), 0_usize);
+        for (i, r) in rects.iter().enumerate() {
+            map.update(*r, |v| *v += i % 3);
+        }
+        let map_time = start.elapsed();
+
+        // Like part 1, where whole regions are often set to one value
+        let start = Instant::now();
+        let mut set = RectSet::new(Rect::of_size(1000, 1000));
+        for (i, r) in rects.iter().enumerate() {
+            match i % 3 {
+                0 => set.insert(*r),
+                1 => set.remove(*r),
+                _ => set.toggle(*r),
+            }
+        }
+        let set_time = start.elapsed();
+
+        assert_eq!(grid.iter().sum::<usize>(), map.sum_by(|&v| v));
+        assert!(
+            map_time <= grid_time,
+            "The map shouldn't be slower than a grid"
+        );
+        println!(
+            "grid: {grid_time:?}, map: {map_time:?} ({} rectangles), set: {set_time:?} ({} rectangles)",
+            map.num_rects(),
+            set.0.num_rects()
+        );
+    }
+}
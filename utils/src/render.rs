@@ -0,0 +1,306 @@
+//! Rendering grids as images, to see what a puzzle (or a bug) looks like.
+//!
+//! Solvers can save images (or numbered frames of an animation) with [save_png] and [Frames],
+//! which do nothing unless visualizations are [enabled] with the runner's `--visualize` flag, so
+//! they cost nothing in normal runs. Any extra work done just to build an image (or a frame)
+//! should be skipped the same way, by checking [enabled] or [Frames::enabled] first. Images are
+//! saved under [OUTPUT_DIR].
+//!
+//! Images are encoded by hand rather than with an image crate: PPMs are trivial, and PNGs are
+//! written with uncompressed ("stored") deflate blocks, which every viewer understands.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::grid::Grid;
+
+/// The directory visualizations are saved in, relative to where the runner is run from
+pub const OUTPUT_DIR: &str = "target/visualize";
+
+/// Whether to save visualizations; see [enable]
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Have [save_png] and [Frames] actually save images, e.g. for the runner's `--visualize` flag
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Whether visualizations are enabled
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Save a grid as `<name>.png` in the [OUTPUT_DIR], with one pixel per cell colored by `color`,
+/// if visualizations are [enabled].
+pub fn save_png<T>(name: &str, grid: &Grid<T>, color: impl Fn(&T) -> Rgb) -> io::Result<()> {
+    if enabled() {
+        save_png_in(Path::new(OUTPUT_DIR), name, grid, color)?;
+    }
+    Ok(())
+}
+
+fn save_png_in<T>(
+    dir: &Path,
+    name: &str,
+    grid: &Grid<T>,
+    color: impl Fn(&T) -> Rgb,
+) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{name}.png"));
+    fs::write(&path, png(grid, color))?;
+    Ok(path)
+}
+
+/// The frames of an animation (e.g., of each step of a simulation), saved as numbered PNG images
+/// (`00000.png`, `00001.png`, ...) in a directory of the [OUTPUT_DIR], if visualizations are
+/// [enabled]. Tools like `ffmpeg` can stitch them together into a video or GIF.
+#[derive(Debug)]
+pub struct Frames {
+    /// Where to save frames, if they're being saved at all
+    dir: Option<PathBuf>,
+    count: usize,
+}
+
+impl Frames {
+    /// Start saving frames in the `<name>` directory of the [OUTPUT_DIR], replacing any frames
+    /// already there
+    pub fn new(name: &str) -> io::Result<Self> {
+        if enabled() {
+            Self::in_dir(Path::new(OUTPUT_DIR).join(name))
+        } else {
+            Ok(Self {
+                dir: None,
+                count: 0,
+            })
+        }
+    }
+
+    fn in_dir(dir: PathBuf) -> io::Result<Self> {
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)?;
+
+        Ok(Self {
+            dir: Some(dir),
+            count: 0,
+        })
+    }
+
+    /// Whether frames are being saved
+    pub fn enabled(&self) -> bool {
+        self.dir.is_some()
+    }
+
+    /// The number of frames saved so far
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Save the next frame
+    pub fn push<T>(&mut self, grid: &Grid<T>, color: impl Fn(&T) -> Rgb) -> io::Result<()> {
+        if let Some(dir) = &self.dir {
+            save_png_in(dir, &format!("{:05}", self.count), grid, color)?;
+            self.count += 1;
+        }
+        Ok(())
+    }
+}
+
+/// The color of a pixel, as red, green, and blue components
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+
+/// Encode a grid as a (binary, "P6") PPM image, with one pixel per cell colored by `color`
+pub fn ppm<T>(grid: &Grid<T>, color: impl Fn(&T) -> Rgb) -> Vec<u8> {
+    let mut image = format!("P6\n{} {}\n255\n", grid.cols(), grid.rows()).into_bytes();
+    image.extend(grid.iter().flat_map(color));
+    image
+}
+
+/// Encode a grid as a PNG image, with one pixel per cell colored by `color`
+pub fn png<T>(grid: &Grid<T>, color: impl Fn(&T) -> Rgb) -> Vec<u8> {
+    let mut image = b"\x89PNG\r\n\x1a\n".to_vec();
+
+    let mut header = Vec::with_capacity(13);
+    header.extend((grid.cols() as u32).to_be_bytes());
+    header.extend((grid.rows() as u32).to_be_bytes());
+    header.extend([8, 2, 0, 0, 0]); // 8-bit RGB, default compression & filtering, no interlacing
+    write_chunk(&mut image, b"IHDR", &header);
+
+    // Each row of pixels starts with the type of filter applied to it (none)
+    let mut pixels = Vec::with_capacity(grid.rows() * (grid.cols() * 3 + 1));
+    for row in grid.iter_rows() {
+        pixels.push(0);
+        pixels.extend(row.iter().flat_map(&color));
+    }
+    write_chunk(&mut image, b"IDAT", &zlib_stored(&pixels));
+
+    write_chunk(&mut image, b"IEND", &[]);
+    image
+}
+
+/// Write a PNG chunk: its length, type, data, and the CRC of its type & data
+fn write_chunk(image: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    image.extend((data.len() as u32).to_be_bytes());
+    let start = image.len();
+    image.extend(kind);
+    image.extend(data);
+    let crc = crc32(&image[start..]);
+    image.extend(crc.to_be_bytes());
+}
+
+/// Wrap data in a zlib stream without compressing it
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = u16::MAX as usize;
+
+    let mut stream = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        // Even no data needs a (final, empty) block
+        stream.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        stream.push(last as u8);
+        stream.extend(len.to_le_bytes());
+        stream.extend((!len).to_le_bytes());
+        stream.extend(block);
+    }
+    stream.extend(adler32(data).to_be_bytes());
+    stream
+}
+
+/// The CRC-32 checksum used by PNG (and zip, gzip, ...)
+fn crc32(data: &[u8]) -> u32 {
+    let table: Vec<u32> = (0..256)
+        .map(|n| {
+            (0..8).fold(n, |c, _| {
+                if c & 1 == 1 {
+                    0xedb88320 ^ (c >> 1)
+                } else {
+                    c >> 1
+                }
+            })
+        })
+        .collect();
+
+    !data.iter().fold(!0, |crc, &b| {
+        table[((crc ^ u32::from(b)) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+/// The Adler-32 checksum used by zlib
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (a, b) = data.iter().fold((1, 0), |(a, b), &byte| {
+        let a = (a + u32::from(byte)) % MOD;
+        (a, (b + a) % MOD)
+    });
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkerboard() -> Grid<bool> {
+        let mut grid = Grid::new(2, 3, false);
+        for (p, cell) in grid.region_mut((0, 0), (1, 2)) {
+            *cell = (p.row + p.col) % 2 == 0;
+        }
+        grid
+    }
+
+    fn color(&on: &bool) -> Rgb {
+        if on {
+            WHITE
+        } else {
+            BLACK
+        }
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+        assert_eq!(crc32(b"IEND"), 0xae426082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e60398);
+    }
+
+    #[test]
+    fn encode_ppm() {
+        let image = ppm(&checkerboard(), color);
+        let mut expected = b"P6\n3 2\n255\n".to_vec();
+        for on in [true, false, true, false, true, false] {
+            expected.extend(color(&on));
+        }
+        assert_eq!(image, expected);
+    }
+
+    #[test]
+    fn encode_png() {
+        let image = png(&checkerboard(), color);
+        assert!(image.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert!(image.ends_with(b"IEND\xae\x42\x60\x82"));
+
+        // The header gives the width & height
+        assert_eq!(&image[12..16], b"IHDR");
+        assert_eq!(&image[16..24], &[0, 0, 0, 3, 0, 0, 0, 2]);
+
+        // The pixel data is stored as is, after the zlib & block headers
+        let data = &image[33 + 8..];
+        assert_eq!(&data[..2], &[0x78, 0x01]);
+        assert_eq!(&data[2..7], &[1, 20, 0, !20, 0xff]);
+        assert_eq!(&data[7..11], &[0, 255, 255, 255]);
+    }
+
+    #[test]
+    fn saving() {
+        // Nothing is saved unless visualizations are enabled (which no test does)
+        let mut frames = Frames::new("test").unwrap();
+        assert!(!frames.enabled());
+        frames.push(&checkerboard(), color).unwrap();
+        assert!(frames.is_empty());
+
+        let dir = std::env::temp_dir().join(format!("utils-render-{}", std::process::id()));
+        let path = save_png_in(&dir, "board", &checkerboard(), color).unwrap();
+        assert_eq!(fs::read(path).unwrap(), png(&checkerboard(), color));
+
+        let mut frames = Frames::in_dir(dir.join("frames")).unwrap();
+        for _ in 0..3 {
+            frames.push(&checkerboard(), color).unwrap();
+        }
+        assert_eq!(frames.len(), 3);
+        assert!(dir.join("frames/00002.png").exists());
+
+        // Starting again replaces the old frames
+        let frames = Frames::in_dir(dir.join("frames")).unwrap();
+        assert!(frames.is_empty());
+        assert!(!dir.join("frames/00000.png").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn stored_blocks() {
+        let data = vec![7; 70_000];
+        let stream = zlib_stored(&data);
+        // Two blocks: a full one and the (final) rest
+        assert_eq!(stream.len(), 2 + 5 + 65535 + 5 + (70_000 - 65535) + 4);
+        assert_eq!(stream[2], 0);
+        assert_eq!(stream[2 + 5 + 65535], 1);
+        assert_eq!(
+            zlib_stored(&[]),
+            vec![0x78, 0x01, 1, 0, 0, 0xff, 0xff, 0, 0, 0, 1]
+        );
+    }
+}
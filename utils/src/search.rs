@@ -0,0 +1,467 @@
+//! Generic graph searches. Graphs are never built up front: each search takes a closure giving the
+//! neighbors of a node, so the same functions work for a [Grid](crate::grid::Grid) (e.g.,
+//! `|&p| grid.neighbors4(p)`), a map of dependencies, or a puzzle state that's generated on the
+//! fly.
+//!
+//! Nodes need to be `Clone + Eq + Hash`; costs can be any ordered type with a `Default` of zero
+//! (e.g., any of the integer types).
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Follow the `parent` links back from `end` to build the path to it, from the start of the
+/// search.
+fn path<N: Clone + Eq + Hash>(parents: &HashMap<N, N>, end: N) -> Vec<N> {
+    let mut path = vec![end];
+    while let Some(parent) = parents.get(path.last().unwrap()) {
+        path.push(parent.clone());
+    }
+
+    path.reverse();
+    path
+}
+
+/// Find the shortest path (by number of steps) from `start` to a node for which `is_goal` is
+/// true, with a breadth-first search. The path includes both `start` and the goal.
+pub fn bfs<N, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut parents: HashMap<N, N> = HashMap::new();
+    let mut seen = HashSet::from([start.clone()]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            return Some(path(&parents, node));
+        }
+
+        for next in neighbors(&node) {
+            if seen.insert(next.clone()) {
+                parents.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+/// Find the number of steps from `start` to every node reachable from it, with a breadth-first
+/// search.
+pub fn bfs_distances<N, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> HashMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(node) = queue.pop_front() {
+        let dist = distances[&node];
+        for next in neighbors(&node) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), dist + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    distances
+}
+
+/// Find the cheapest path from `start` to a node for which `is_goal` is true, where `neighbors`
+/// gives each neighbor of a node along with the cost of moving to it. Returns the path (including
+/// `start` and the goal) and its total cost.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    neighbors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, neighbors, |_| C::default(), is_goal)
+}
+
+/// Find the cheapest cost from `start` to every node reachable from it; see [dijkstra].
+pub fn dijkstra_all<N, C, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> HashMap<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut costs = HashMap::from([(start.clone(), C::default())]);
+    let mut nodes = vec![start];
+    let mut heap = BinaryHeap::from([(Reverse(C::default()), 0)]);
+
+    while let Some((Reverse(cost), idx)) = heap.pop() {
+        let node = nodes[idx].clone();
+        if costs[&node] < cost {
+            continue; // already found a cheaper way here
+        }
+
+        for (next, step) in neighbors(&node) {
+            let next_cost = cost + step;
+            if costs.get(&next).is_some_and(|&c| c <= next_cost) {
+                continue;
+            }
+
+            costs.insert(next.clone(), next_cost);
+            heap.push((Reverse(next_cost), nodes.len()));
+            nodes.push(next);
+        }
+    }
+
+    costs
+}
+
+/// Find the cheapest path from `start` to a node for which `is_goal` is true, like [dijkstra],
+/// guided by a `heuristic` estimate of the cost from a node to the nearest goal. The heuristic
+/// must never overestimate that cost, or the path found may not be the cheapest.
+pub fn astar<N, C, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut parents: HashMap<N, N> = HashMap::new();
+    let mut costs = HashMap::from([(start.clone(), C::default())]);
+
+    // Nodes are stored separately so that the heap needn't compare them; ties on the estimated
+    // total cost are broken in favor of the node found first.
+    let mut nodes = vec![start.clone()];
+    let mut heap = BinaryHeap::from([(Reverse(heuristic(&start)), Reverse(0))]);
+
+    while let Some((_, Reverse(idx))) = heap.pop() {
+        let node = nodes[idx].clone();
+        let cost = costs[&node];
+        if is_goal(&node) {
+            return Some((path(&parents, node), cost));
+        }
+
+        for (next, step) in neighbors(&node) {
+            let next_cost = cost + step;
+            if costs.get(&next).is_some_and(|&c| c <= next_cost) {
+                continue;
+            }
+
+            costs.insert(next.clone(), next_cost);
+            parents.insert(next.clone(), node.clone());
+            let estimate = next_cost + heuristic(&next);
+            heap.push((Reverse(estimate), Reverse(nodes.len())));
+            nodes.push(next);
+        }
+    }
+
+    None
+}
+
+/// Find every node reachable from `start`, in depth-first (pre-)order.
+pub fn dfs<N, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> Vec<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut order = Vec::new();
+    let mut seen = HashSet::new();
+    let mut stack = vec![start];
+
+    while let Some(node) = stack.pop() {
+        if !seen.insert(node.clone()) {
+            continue;
+        }
+
+        // Push neighbors in reverse, so they're visited in the order they were given
+        let next: Vec<N> = neighbors(&node).into_iter().collect();
+        stack.extend(next.into_iter().rev().filter(|n| !seen.contains(n)));
+        order.push(node);
+    }
+
+    order
+}
+
+/// Sort `nodes` (and anything they depend on) so that each node comes after all of its
+/// dependencies, as given by `deps`. If the dependencies have a cycle, returns the nodes in the
+/// cycle instead, starting from the node first found to be in it.
+pub fn toposort<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut deps: impl FnMut(&N) -> I,
+) -> Result<Vec<N>, Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut order = Vec::new();
+    let mut done: HashSet<N> = HashSet::new();
+
+    for root in nodes {
+        if done.contains(&root) {
+            continue;
+        }
+
+        // Each entry on the stack is a node along with the dependencies yet to be visited; the
+        // nodes on the stack are those being visited, so finding one again means a cycle.
+        let mut root_deps: Vec<N> = deps(&root).into_iter().collect();
+        root_deps.reverse(); // so dependencies are visited in the order given
+        let mut stack = vec![(root.clone(), root_deps)];
+        let mut visiting = HashSet::from([root]);
+
+        while let Some((node, pending)) = stack.last_mut() {
+            match pending.pop() {
+                Some(dep) if done.contains(&dep) => {}
+                Some(dep) if visiting.contains(&dep) => {
+                    let start = stack.iter().position(|(n, _)| *n == dep).unwrap();
+                    return Err(stack.drain(start..).map(|(n, _)| n).collect());
+                }
+                Some(dep) => {
+                    let mut dep_deps: Vec<N> = deps(&dep).into_iter().collect();
+                    dep_deps.reverse(); // so dependencies are visited in the order given
+                    visiting.insert(dep.clone());
+                    stack.push((dep, dep_deps));
+                }
+                None => {
+                    let node = node.clone();
+                    stack.pop();
+                    visiting.remove(&node);
+                    done.insert(node.clone());
+                    order.push(node);
+                }
+            }
+        }
+    }
+
+    Ok(order)
+}
+
+/// Split `nodes` into connected components, where `neighbors` gives the nodes each one is
+/// connected to (in both directions). Each component lists its nodes in the order they're found
+/// by a breadth-first search.
+pub fn components<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+) -> Vec<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen: HashSet<N> = HashSet::new();
+    let mut components = Vec::new();
+
+    for start in nodes {
+        if !seen.insert(start.clone()) {
+            continue;
+        }
+
+        let mut component = Vec::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            for next in neighbors(&node) {
+                if seen.insert(next.clone()) {
+                    queue.push_back(next);
+                }
+            }
+            component.push(node);
+        }
+        components.push(component);
+    }
+
+    components
+}
+
+/// The shape of the sequence `x, f(x), f(f(x)), ...` of a function applied repeatedly: after
+/// `start` steps, it repeats every `len` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Cycle {
+    /// The index of the earlier element of the sequence which is equal to element `n`; handy for
+    /// finding, e.g., the state after a billion steps.
+    pub fn equivalent(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.len
+        }
+    }
+}
+
+/// Find the [Cycle] in the sequence `x0, f(x0), f(f(x0)), ...` using Floyd's "tortoise and hare"
+/// algorithm, which only keeps a couple of values at a time.
+pub fn floyd<T: Clone + Eq>(x0: T, mut f: impl FnMut(&T) -> T) -> Cycle {
+    let mut tortoise = f(&x0);
+    let mut hare = f(&tortoise);
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        let next = f(&hare);
+        hare = f(&next);
+    }
+
+    // The distance from x0 to the start of the cycle is the same as from the meeting point
+    let mut start = 0;
+    tortoise = x0;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        start += 1;
+    }
+
+    let mut len = 1;
+    hare = f(&tortoise);
+    while tortoise != hare {
+        hare = f(&hare);
+        len += 1;
+    }
+
+    Cycle { start, len }
+}
+
+/// Find the [Cycle] in the sequence `x0, f(x0), f(f(x0)), ...` using Brent's algorithm, which
+/// generally calls `f` fewer times than [floyd].
+pub fn brent<T: Clone + Eq>(x0: T, mut f: impl FnMut(&T) -> T) -> Cycle {
+    // Find the length of the cycle, searching in successive powers of two
+    let mut power = 1;
+    let mut len = 1;
+    let mut tortoise = x0.clone();
+    let mut hare = f(&x0);
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+        hare = f(&hare);
+        len += 1;
+    }
+
+    // Then find its start, with the hare a cycle's length ahead of the tortoise
+    let mut start = 0;
+    tortoise = x0.clone();
+    hare = x0;
+    for _ in 0..len {
+        hare = f(&hare);
+    }
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        start += 1;
+    }
+
+    Cycle { start, len }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Grid, Point};
+
+    const MAZE: &str = "\
+S.#.....
+.##.##..
+....#E#.
+.##...#.
+";
+
+    #[test]
+    fn grid_paths() {
+        let grid: Grid<char> = MAZE.parse().unwrap();
+        let start = grid.position(|&c| c == 'S').unwrap();
+        let end = grid.position(|&c| c == 'E').unwrap();
+        let open = |&p: &Point| grid.neighbors4(p).filter(|&q| grid[q] != '#');
+
+        let path = bfs(start, open, |&p| p == end).unwrap();
+        assert_eq!(path.len() - 1, 9);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&end));
+        assert!(path.windows(2).all(|w| open(&w[0]).any(|p| p == w[1])));
+
+        assert_eq!(bfs_distances(start, open)[&end], 9);
+        assert_eq!(bfs(start, open, |&p| p == Point::new(0, 2)), None);
+
+        // Moving onto a '.' costs 1, onto 'E' costs 5
+        let weighted = |&p: &Point| open(&p).map(|q| (q, if grid[q] == 'E' { 5 } else { 1 }));
+        let (path, cost) = dijkstra(start, weighted, |&p| p == end).unwrap();
+        assert_eq!((path.len() - 1, cost), (9, 13));
+        assert_eq!(dijkstra_all(start, weighted)[&end], 13);
+
+        let manhattan = |p: &Point| p.row.abs_diff(end.row) + p.col.abs_diff(end.col);
+        let (path, cost) = astar(start, weighted, manhattan, |&p| p == end).unwrap();
+        assert_eq!((path.len() - 1, cost), (9, 13));
+
+        let reachable = dfs(start, open);
+        assert_eq!(reachable.first(), Some(&start));
+        assert_eq!(reachable.len(), grid.iter().filter(|&&c| c != '#').count());
+    }
+
+    #[test]
+    fn dependencies() {
+        // Like the wires of a circuit, each of which depends on the wires it's connected to
+        let circuit = HashMap::from([
+            ("x", vec![]),
+            ("y", vec![]),
+            ("d", vec!["x", "y"]),
+            ("e", vec!["x", "y"]),
+            ("f", vec!["x"]),
+            ("h", vec!["d", "e"]),
+        ]);
+        let deps = |w: &&str| circuit[w].clone();
+
+        let order = toposort(["h", "f"], deps).unwrap();
+        assert_eq!(order, vec!["x", "y", "d", "e", "h", "f"]);
+
+        let mut cyclic = circuit.clone();
+        cyclic.insert("x", vec!["h"]);
+        let cycle = toposort(["f"], |w: &&str| cyclic[w].clone()).unwrap_err();
+        assert_eq!(cycle, vec!["x", "h", "d"]);
+
+        // Treating the connections as undirected, everything is connected
+        let mut undirected: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (&wire, inputs) in &circuit {
+            for &input in inputs {
+                undirected.entry(wire).or_default().push(input);
+                undirected.entry(input).or_default().push(wire);
+            }
+        }
+        undirected.insert("z", vec![]);
+        let mut sizes: Vec<usize> = components(["x", "z"], |w: &&str| undirected[w].clone())
+            .iter()
+            .map(Vec::len)
+            .collect();
+        sizes.sort();
+        assert_eq!(sizes, vec![1, 6]);
+    }
+
+    #[test]
+    fn cycles() {
+        // 0, 1, 2, then 3, 4, 5, 6, 7 repeating
+        let f = |&x: &u32| if x == 7 { 3 } else { x + 1 };
+        let expected = Cycle { start: 3, len: 5 };
+        assert_eq!(floyd(0, f), expected);
+        assert_eq!(brent(0, f), expected);
+        assert_eq!(expected.equivalent(2), 2);
+        assert_eq!(expected.equivalent(8), 3);
+        assert_eq!(expected.equivalent(1_000_000_002), 7);
+
+        // A cycle right from the start
+        let f = |&x: &u32| (x + 1) % 4;
+        assert_eq!(floyd(0, f), Cycle { start: 0, len: 4 });
+        assert_eq!(brent(0, f), Cycle { start: 0, len: 4 });
+    }
+}
@@ -0,0 +1,472 @@
+//! Animating simulations in the terminal, redrawing a viewport of a grid (or a set of points) with
+//! ANSI colors each step.
+//!
+//! An [Animation] does nothing unless animations are [enabled] with the runner's `--animate` flag
+//! and stdout is a terminal, so solvers can draw frames unconditionally (but see
+//! [render](crate::render) about work done just to build them). While one is running:
+//!
+//! * `space` pauses or resumes it
+//! * `n` draws the next frame while paused
+//! * `+` and `-` double or halve the frame rate
+//! * `q` stops drawing, letting the solver finish at full speed
+//! * `Ctrl-C` restores the terminal and exits
+//!
+//! Keys are read by putting the terminal in raw mode with `stty`, so they're only available where
+//! that exists; elsewhere, animations still play but can't be controlled.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, IsTerminal, Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::geom::Point2;
+use crate::grid::{Grid, Point};
+use crate::render::Rgb;
+
+/// Whether to animate; see [enable]
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The initial number of frames per second for animations
+static FPS: AtomicU32 = AtomicU32::new(30);
+
+/// Have [Animation]s actually draw (when stdout is a terminal), at the given frame rate, e.g. for
+/// the runner's `--animate` flag
+pub fn enable(fps: u32) {
+    ENABLED.store(true, Ordering::Relaxed);
+    FPS.store(fps.max(1), Ordering::Relaxed);
+}
+
+/// Whether animations are enabled (though they're still skipped if stdout isn't a terminal)
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// A single character drawn in the terminal, optionally in color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub color: Option<Rgb>,
+}
+
+impl Cell {
+    pub const EMPTY: Cell = Cell::plain(' ');
+
+    pub const fn plain(ch: char) -> Self {
+        Self { ch, color: None }
+    }
+
+    pub const fn colored(ch: char, color: Rgb) -> Self {
+        Self {
+            ch,
+            color: Some(color),
+        }
+    }
+}
+
+/// An animation drawn in the terminal, one frame at a time
+pub struct Animation {
+    title: String,
+
+    /// The number of rows & columns of cells to draw
+    height: usize,
+    width: usize,
+
+    /// Whether to draw at all, which stops being true if the animation is skipped
+    active: bool,
+    paused: bool,
+    frame_time: Duration,
+    frames: usize,
+    last_frame: Instant,
+    keys: Option<Keys>,
+
+    /// The points added by [Animation::plot], which are kept between frames
+    canvas: HashMap<(i64, i64), Cell>,
+}
+
+impl Animation {
+    /// Start an animation, if animations are [enabled] and stdout is a terminal. The viewport
+    /// fills the terminal (as given by `stty size`, or 80x24), less a status line.
+    pub fn new(title: &str) -> Self {
+        let active = enabled() && io::stdout().is_terminal();
+        let (rows, cols) = active.then(terminal_size).flatten().unwrap_or((24, 80));
+
+        let mut animation = Self {
+            title: title.to_string(),
+            height: rows.saturating_sub(1).max(1),
+            width: cols.max(1),
+            active,
+            paused: false,
+            frame_time: Duration::from_secs(1) / FPS.load(Ordering::Relaxed),
+            frames: 0,
+            last_frame: Instant::now(),
+            keys: None,
+            canvas: HashMap::new(),
+        };
+
+        if active {
+            animation.keys = Keys::read();
+            // Clear the screen & hide the cursor
+            print!("\x1b[2J\x1b[?25l");
+        }
+
+        animation
+    }
+
+    /// Use a viewport of the given size, rather than filling the terminal
+    pub fn with_viewport(mut self, width: usize, height: usize) -> Self {
+        self.width = width.max(1);
+        self.height = height.max(1);
+        self
+    }
+
+    /// Whether frames are being drawn
+    pub fn enabled(&self) -> bool {
+        self.active
+    }
+
+    /// Draw the part of a grid around `focus` (as near the middle of the viewport as possible)
+    /// with each value drawn by `cell`, then wait for the next frame.
+    pub fn grid<T>(&mut self, grid: &Grid<T>, focus: impl Into<Point>, cell: impl Fn(&T) -> Cell) {
+        if self.active {
+            let frame = grid_frame(grid, focus.into(), self.width, self.height, cell);
+            self.show(frame);
+        }
+    }
+
+    /// Draw the points around `focus` (in the middle of the viewport), where `y` increases
+    /// upwards, then wait for the next frame. Points drawn later are drawn over earlier ones, and
+    /// all of them over the ones [plotted](Animation::plot) so far.
+    pub fn points<T: Copy + Into<i64>>(
+        &mut self,
+        points: impl IntoIterator<Item = (Point2<T>, Cell)>,
+        focus: Point2<T>,
+    ) {
+        if self.active {
+            let frame = points_frame(&self.canvas, points, focus, self.width, self.height);
+            self.show(frame);
+        }
+    }
+
+    /// Add a point to be drawn underneath every later frame of [points](Animation::points),
+    /// replacing any already there, so that something which only grows (like a path) doesn't have
+    /// to be passed in full each frame
+    pub fn plot<T: Into<i64>>(&mut self, p: Point2<T>, cell: Cell) {
+        if self.active {
+            self.canvas.insert((p.x.into(), p.y.into()), cell);
+        }
+    }
+
+    /// Draw a frame (along with a status line), then wait until it's time for the next one
+    fn show(&mut self, frame: String) {
+        self.frames += 1;
+        let status = format!(
+            "{} | frame {} | {:.0} fps{} | [space] pause [n] step [+/-] speed [q] skip",
+            self.title,
+            self.frames,
+            1.0 / self.frame_time.as_secs_f64(),
+            if self.paused { " (paused)" } else { "" },
+        );
+        // Wrapping onto another line would scroll the frame
+        let status: String = status.chars().take(self.width).collect();
+
+        let mut out = io::stdout().lock();
+        let _ = write!(out, "\x1b[H{frame}\x1b[7m{status}\x1b[0m\x1b[K");
+        let _ = out.flush();
+        drop(out);
+
+        self.wait();
+    }
+
+    /// Handle any keys pressed, until it's time for the next frame
+    fn wait(&mut self) {
+        let mut step = false;
+        loop {
+            while let Some(key) = self.keys.as_ref().and_then(|k| k.pressed.try_recv().ok()) {
+                match key {
+                    b' ' => self.paused = !self.paused,
+                    b'n' => step = true,
+                    b'+' | b'=' => {
+                        self.frame_time = (self.frame_time / 2).max(Duration::from_micros(100))
+                    }
+                    b'-' => self.frame_time = (self.frame_time * 2).min(Duration::from_secs(10)),
+                    b'q' => {
+                        self.finish();
+                        return;
+                    }
+                    // Ctrl-C doesn't interrupt the process in raw mode, so that the terminal can
+                    // be restored first
+                    0x03 => {
+                        self.finish();
+                        std::process::exit(130);
+                    }
+                    _ => {}
+                }
+            }
+
+            let elapsed = self.last_frame.elapsed();
+            if (self.paused && step) || (!self.paused && elapsed >= self.frame_time) {
+                self.last_frame = Instant::now();
+                return;
+            }
+
+            let remaining = self.frame_time.saturating_sub(elapsed);
+            thread::sleep(remaining.min(Duration::from_millis(10)));
+        }
+    }
+
+    /// Stop drawing frames, leaving the last one on the screen
+    fn finish(&mut self) {
+        if self.active {
+            self.active = false;
+            self.keys = None;
+            // Show the cursor again, below the last frame
+            println!("\x1b[?25h");
+        }
+    }
+}
+
+impl Drop for Animation {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Draw the `width` by `height` part of a grid around `focus`
+fn grid_frame<T>(
+    grid: &Grid<T>,
+    focus: Point,
+    width: usize,
+    height: usize,
+    cell: impl Fn(&T) -> Cell,
+) -> String {
+    // Center the viewport on the focus, but keep it on the grid where possible
+    let start = |focus: usize, view: usize, len: usize| {
+        focus.saturating_sub(view / 2).min(len.saturating_sub(view))
+    };
+    let top = start(focus.row, height, grid.rows());
+    let left = start(focus.col, width, grid.cols());
+
+    draw(width, height, |row, col| {
+        grid.get((top + row, left + col)).map_or(Cell::EMPTY, &cell)
+    })
+}
+
+/// Draw the `width` by `height` area around `focus` of a set of points over a canvas of earlier
+/// ones, where `y` increases upwards
+fn points_frame<T: Copy + Into<i64>>(
+    canvas: &HashMap<(i64, i64), Cell>,
+    points: impl IntoIterator<Item = (Point2<T>, Cell)>,
+    focus: Point2<T>,
+    width: usize,
+    height: usize,
+) -> String {
+    let (w, h) = (width as i64, height as i64);
+    let left = focus.x.into() - w / 2;
+    let top = focus.y.into() + h / 2;
+
+    // Only look up the canvas's points in view, however many there are in total
+    let mut cells: Vec<Cell> = (0..h)
+        .flat_map(|row| (0..w).map(move |col| (left + col, top - row)))
+        .map(|p| canvas.get(&p).copied().unwrap_or(Cell::EMPTY))
+        .collect();
+    for (p, cell) in points {
+        let (col, row) = (p.x.into() - left, top - p.y.into());
+        if (0..w).contains(&col) && (0..h).contains(&row) {
+            cells[(row * w + col) as usize] = cell;
+        }
+    }
+
+    draw(width, height, |row, col| cells[row * width + col])
+}
+
+/// Draw each row of cells, clearing the rest of each line, and only changing colors as needed
+fn draw(width: usize, height: usize, cell: impl Fn(usize, usize) -> Cell) -> String {
+    let mut frame = String::new();
+    for row in 0..height {
+        let mut color = None;
+        for col in 0..width {
+            let c = cell(row, col);
+            if c.color != color {
+                match c.color {
+                    Some([r, g, b]) => write!(frame, "\x1b[38;2;{r};{g};{b}m").unwrap(),
+                    None => frame.push_str("\x1b[0m"),
+                }
+                color = c.color;
+            }
+            frame.push(c.ch);
+        }
+        if color.is_some() {
+            frame.push_str("\x1b[0m");
+        }
+        frame.push_str("\x1b[K\n");
+    }
+
+    frame
+}
+
+/// The keys pressed in the terminal, read by a thread while the terminal is in raw mode (no line
+/// buffering, echo, or signals from keys like Ctrl-C). Dropping this stops the thread and restores
+/// the terminal, so keys pressed afterwards are left alone.
+struct Keys {
+    pressed: Receiver<u8>,
+    stop: Arc<AtomicBool>,
+    reader: Option<JoinHandle<()>>,
+
+    /// The terminal's settings before entering raw mode, to restore afterwards
+    saved: String,
+}
+
+impl Keys {
+    fn read() -> Option<Self> {
+        let mut tty = File::open("/dev/tty").ok()?;
+        let saved = stty(&["-g"])?;
+        // Reads give up after a tenth of a second without a key, so the thread can check
+        // whether to stop
+        stty(&["-icanon", "-echo", "-isig", "min", "0", "time", "1"])?;
+
+        let (tx, pressed) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let reader = thread::spawn({
+            let stop = Arc::clone(&stop);
+            move || {
+                let mut key = [0];
+                while !stop.load(Ordering::Relaxed) {
+                    match tty.read(&mut key) {
+                        Ok(0) => {}
+                        Ok(_) if tx.send(key[0]).is_ok() => {}
+                        _ => break,
+                    }
+                }
+            }
+        });
+
+        Some(Self {
+            pressed,
+            stop,
+            reader: Some(reader),
+            saved: saved.trim().to_string(),
+        })
+    }
+}
+
+impl Drop for Keys {
+    fn drop(&mut self) {
+        // Wait for the reader to stop before leaving raw mode, when its read could block
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(reader) = self.reader.take() {
+            let _ = reader.join();
+        }
+        stty(&[&self.saved]);
+    }
+}
+
+/// The number of rows & columns in the terminal
+fn terminal_size() -> Option<(usize, usize)> {
+    let size = stty(&["size"])?;
+    let (rows, cols) = size.trim().split_once(' ')?;
+    Some((rows.parse().ok()?, cols.parse().ok()?))
+}
+
+/// Run `stty` on the controlling terminal, returning its output if it succeeds
+fn stty(args: &[&str]) -> Option<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(File::open("/dev/tty").ok()?)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The text of a frame, without its escape codes
+    fn plain(frame: &str) -> String {
+        let mut text = String::new();
+        let mut chars = frame.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                // Skip to the end of the escape code
+                chars.by_ref().find(|c| c.is_ascii_alphabetic());
+            } else {
+                text.push(c);
+            }
+        }
+        text
+    }
+
+    #[test]
+    fn disabled() {
+        // Animations aren't enabled in tests (nor is stdout a terminal)
+        let mut animation = Animation::new("test");
+        assert!(!animation.enabled());
+        animation.plot(Point2::new(0, 0), Cell::plain('#'));
+        animation.points([(Point2::new(0, 0), Cell::plain('#'))], Point2::new(0, 0));
+        assert_eq!(animation.frames, 0);
+        assert!(animation.canvas.is_empty());
+    }
+
+    #[test]
+    fn grid_viewport() {
+        let grid: Grid<char> = "abcde\nfghij\nklmno\npqrst".parse().unwrap();
+
+        // Centered on the focus...
+        let frame = grid_frame(&grid, Point::new(2, 2), 3, 2, |&c| Cell::plain(c));
+        assert_eq!(plain(&frame), "ghi\nlmn\n");
+
+        // ... but kept on the grid
+        let frame = grid_frame(&grid, Point::new(0, 4), 3, 2, |&c| Cell::plain(c));
+        assert_eq!(plain(&frame), "cde\nhij\n");
+
+        // Unless the grid is smaller than the viewport
+        let frame = grid_frame(&grid, Point::new(0, 0), 6, 1, |&c| Cell::plain(c));
+        assert_eq!(plain(&frame), "abcde \n");
+    }
+
+    #[test]
+    fn points_viewport() {
+        let points = [
+            (Point2::new(0, 0), Cell::plain('o')),
+            (Point2::new(1, 1), Cell::plain('x')),
+            (Point2::new(-1, -1), Cell::plain('y')),
+            (Point2::new(10, 10), Cell::plain('!')),
+        ];
+        let frame = points_frame(&HashMap::new(), points, Point2::new(0, 0), 3, 3);
+        assert_eq!(plain(&frame), "  x\n o \ny  \n");
+    }
+
+    #[test]
+    fn points_over_canvas() {
+        let canvas = HashMap::from([
+            ((0, 0), Cell::plain('.')),
+            ((1, 0), Cell::plain('.')),
+            ((5, 5), Cell::plain('!')),
+        ]);
+        let frame = points_frame(
+            &canvas,
+            [(Point2::new(0, 0), Cell::plain('@'))],
+            (0, 0).into(),
+            3,
+            1,
+        );
+        assert_eq!(plain(&frame), " @.\n");
+    }
+
+    #[test]
+    fn colors() {
+        let red = Cell::colored('#', [255, 0, 0]);
+        let frame = draw(3, 1, |_, col| if col < 2 { red } else { Cell::plain('.') });
+        assert_eq!(frame, "\x1b[38;2;255;0;0m##\x1b[0m.\x1b[K\n");
+    }
+}